@@ -7,13 +7,18 @@ use raytrs::material::ColorTexture;
 use raytrs::material::Dilectric;
 use raytrs::material::Lambertian;
 use raytrs::material::Metal;
+use raytrs::render::IntegratorType;
+use raytrs::render::PhotonSettings;
 use raytrs::render::RenderMode;
+use raytrs::render::RenderOptions;
 use raytrs::render::Renderer;
 use raytrs::render::Scene;
-use raytrs::shape::Sphere;
-use raytrs::utils::random;
+use raytrs::shape::{Aabb, Sphere};
+use raytrs::utils::{random, Rng};
 
-fn main() {
+/// Builds the scene of random spheres, which is the same for the same `seed`.
+fn random_spheres(seed: u64) -> Scene {
+    let mut rng = Rng::new(seed);
     let mut scene = Scene::new(Camera::new(
         Vec3::new(4.0, 0.0, 0.0),
         Vec3::new(0.0, 2.0, 0.0),
@@ -32,21 +37,21 @@ fn main() {
 
     for a in -11..11 {
         for b in -11..11 {
-            let material_choice = random::<f64>();
+            let material_choice = random::<f64>(&mut rng);
             let center = Vec3::new(
-                a as f64 + 0.9 * random::<f64>(),
+                a as f64 + 0.9 * random::<f64>(&mut rng),
                 0.2,
-                b as f64 + 0.9 * random::<f64>(),
+                b as f64 + 0.9 * random::<f64>(&mut rng),
             );
 
             if (center - Vec3::new(4.0, 0.2, 0.0)).norm() > 0.9 {
                 if material_choice < 0.8 {
-                    let albedo = Box::new(ColorTexture::new(Color::random()));
+                    let albedo = Box::new(ColorTexture::new(Color::random(&mut rng)));
                     let sphere = Sphere::new(center, 0.2, Arc::new(Lambertian::new(albedo)));
                     scene.push(Box::new(sphere));
                 } else if material_choice < 0.95 {
-                    let albedo = Box::new(ColorTexture::new(Color::random()));
-                    let fuzz = random::<f64>();
+                    let albedo = Box::new(ColorTexture::new(Color::random(&mut rng)));
+                    let fuzz = random::<f64>(&mut rng);
                     let sphere = Sphere::new(center, 0.2, Arc::new(Metal::new(albedo, fuzz)));
                     scene.push(Box::new(sphere));
                 } else {
@@ -77,10 +82,39 @@ fn main() {
             0.0,
         )),
     )));
+    scene.set_seed(seed);
+    scene
+}
+
+fn main() {
+    // The seed can be given as the first argument.
+    let seed = std::env::args()
+        .nth(1)
+        .map_or(0, |arg| arg.parse().expect("seed must be an integer"));
+    let mut scene = random_spheres(seed);
 
     let img_basic = scene.render(RenderMode::BASIC);
     img_basic.save("basic.png").unwrap();
 
     let img_aa = scene.render(RenderMode::AA(10));
     img_aa.save("aa.png").unwrap();
+
+    let img_whitted = scene.render(RenderMode::WHITTED);
+    img_whitted.save("whitted.png").unwrap();
+
+    // The photons from the sky are focused on the ground around the spheres, not on the whole ground sphere.
+    scene.set_photon_settings(PhotonSettings {
+        photons: 200_000,
+        radius: 0.1,
+        bounds: Some(Aabb::new(
+            Vec3::new(-12.0, 0.0, -12.0),
+            Vec3::new(12.0, 2.2, 12.0),
+        )),
+        ..PhotonSettings::default()
+    });
+    let photon_mapping = IntegratorType::ProgressivePhotonMapping.build(None);
+    let img_photon = scene.render_with(
+        &RenderOptions::new(RenderMode::AA(10)).with_integrator(photon_mapping.as_ref()),
+    );
+    img_photon.save("photon.png").unwrap();
 }
@@ -117,6 +117,43 @@ impl Camera {
         }
     }
 
+    /// Changes the image resolution keeping the vertical FOV and the view center.
+    ///
+    /// # Arguments
+    /// * `width`   - The image width.
+    /// * `height`  - The image height.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::camera::Camera;
+    /// use raytrs::geometry::Vec3;
+    ///
+    /// let mut cam = Camera::from_lookat(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     90.0,
+    ///     200,
+    ///     100,
+    /// );
+    /// cam.resize(100, 100);
+    /// assert!((cam.u - Vec3::new(2.0, 0.0, 0.0)).is_close(0.0));
+    /// assert!((cam.ray(0.5, 0.5).direction - Vec3::new(0.0, 0.0, -1.0)).is_close(0.0));
+    /// ```
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let center = self.w + 0.5 * self.u + 0.5 * self.v;
+        let scale = (width as f64 / height as f64) / (self.width as f64 / self.height as f64);
+        self.u = scale * self.u;
+        self.w = center - 0.5 * self.u - 0.5 * self.v;
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Returns the unit vector of the view direction through the image center.
+    pub fn forward(&self) -> Vec3 {
+        (self.w + 0.5 * self.u + 0.5 * self.v - self.origin).as_unit()
+    }
+
     /// Returns the ray with the normalized pixel positions `u`, `v`.
     /// The ray direction $\vec{p}$ is as follows.
     ///
@@ -133,4 +170,68 @@ impl Camera {
             direction: self.w + self.u * u + self.v * v - self.origin,
         }
     }
+
+    /// Returns the normalized pixel positions `u`, `v` of the ray from the origin toward `p`,
+    /// which is the inverse of `ray`, or `None` if `p` is out of the view.
+    ///
+    /// # Arguments
+    /// * `p`   - The point seen by the camera.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::camera::Camera;
+    /// use raytrs::geometry::Vec3;
+    ///
+    /// let cam = Camera::from_lookat(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     90.0,
+    ///     200,
+    ///     100,
+    /// );
+    /// let (u, v) = cam.project(cam.ray(0.25, 0.75).at(3.0)).unwrap();
+    /// assert!((u - 0.25).abs() < 1e-12 && (v - 0.75).abs() < 1e-12);
+    /// assert!(cam.project(Vec3::new(0.0, 0.0, 1.0)).is_none());
+    /// ```
+    pub fn project(&self, p: Vec3) -> Option<(f64, f64)> {
+        let n = self.u.cross(self.v);
+        let d = p - self.origin;
+        let (num, den) = ((self.w - self.origin).dot(n), d.dot(n));
+        // The point must be in front of the camera, on the same side as the image plane.
+        if den == 0.0 || num / den <= 0.0 {
+            return None;
+        }
+        let q = self.origin + d * (num / den) - self.w;
+        let (uu, uv, vv) = (self.u.dot(self.u), self.u.dot(self.v), self.v.dot(self.v));
+        let (qu, qv) = (q.dot(self.u), q.dot(self.v));
+        let det = uu * vv - uv * uv;
+        let u = (qu * vv - qv * uv) / det;
+        let v = (qv * uu - qu * uv) / det;
+        ((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v)).then_some((u, v))
+    }
+
+    /// Returns the PDF in solid angle of the ray direction `d` through the uniformly sampled image position,
+    /// or `0.0` if `d` is out of the view.
+    ///
+    /// $$
+    /// p = \frac{h^2}{A\cos^3\theta}
+    /// $$
+    ///
+    /// where $A$ is the area of the image plane, $h$ is its distance and $\theta$ is the angle of `d` to its normal.
+    /// This is also the importance of the camera times $\cos\theta$.
+    ///
+    /// # Arguments
+    /// * `d`   - The direction from the origin.
+    pub fn pdf_direction(&self, d: Vec3) -> f64 {
+        if self.project(self.origin + d).is_none() {
+            return 0.0;
+        }
+        let n = self.u.cross(self.v);
+        let area = n.norm();
+        let n = n / area;
+        let h = (self.w - self.origin).dot(n).abs();
+        let cos = d.as_unit().dot(n).abs();
+        h * h / (area * cos.powi(3))
+    }
 }
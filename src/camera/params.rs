@@ -13,6 +13,12 @@ pub struct FOVParams {
     pub look_at: Vec3,
 }
 
+impl Default for FOVParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FOVParams {
     pub fn new() -> Self {
         FOVParams {
@@ -39,6 +45,12 @@ pub struct PixelParams {
     pub w: Vec3,
 }
 
+impl Default for PixelParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PixelParams {
     pub fn new() -> Self {
         PixelParams {
@@ -58,6 +70,12 @@ pub struct DefocusParams {
     pub defocus_disk_v: Vec3,
 }
 
+impl Default for DefocusParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DefocusParams {
     pub fn new() -> Self {
         DefocusParams {
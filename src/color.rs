@@ -1,5 +1,5 @@
 // use crate::interval::Interval;
-use crate::utils::random;
+use crate::utils::{random, Rng};
 
 /// A struct to represent RGB color.
 #[derive(Debug, Clone, Copy)]
@@ -35,14 +35,68 @@ impl Color {
         Color { r, g, b }
     }
 
+    /// Returns `true`, if all channels are zero.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    ///
+    /// assert!(Color::BLACK.is_black());
+    /// assert!(!Color::RED.is_black());
+    /// ```
+    pub fn is_black(&self) -> bool {
+        self.r == 0.0 && self.g == 0.0 && self.b == 0.0
+    }
+
     /// Constructs random color.
-    pub fn random() -> Self {
-        let r = random::<f64>();
-        let g = random::<f64>();
-        let b = random::<f64>();
+    ///
+    /// # Arguments
+    /// * `rng` - The random generator.
+    pub fn random(rng: &mut Rng) -> Self {
+        let r = random::<f64>(rng);
+        let g = random::<f64>(rng);
+        let b = random::<f64>(rng);
         Color { r, g, b }
     }
 
+    /// Returns the relative luminance with the Rec. 709 coefficients.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    ///
+    /// assert!((Color::WHITE.luminance() - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn luminance(&self) -> f64 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// Returns the color of heatmap, which goes blue, cyan, green, yellow and red as `t` increases.
+    ///
+    /// # Arguments
+    /// * `t`   - The value in `[0.0, 1.0]`, which is clamped.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    ///
+    /// assert_eq!(Color::heatmap(0.0).to_rgb(), Color::BLUE.to_rgb());
+    /// assert_eq!(Color::heatmap(0.5).to_rgb(), Color::GREEN.to_rgb());
+    /// assert_eq!(Color::heatmap(1.0).to_rgb(), Color::RED.to_rgb());
+    /// ```
+    pub fn heatmap(t: f64) -> Self {
+        const STOPS: [Color; 5] = [
+            Color::BLUE,
+            Color::new(0.0, 1.0, 1.0),
+            Color::GREEN,
+            Color::new(1.0, 1.0, 0.0),
+            Color::RED,
+        ];
+        let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+        let i = (t as usize).min(STOPS.len() - 2);
+        STOPS[i].lerp(STOPS[i + 1], t - i as f64)
+    }
+
     pub fn lerp(&self, c: Self, t: f64) -> Self {
         *self + (c - *self) * t
     }
@@ -52,6 +106,28 @@ impl Color {
         Color::new(self.r.powf(inv), self.g.powf(inv), self.b.powf(inv))
     }
 
+    /// Decodes the sRGB-encoded color into linear values by the sRGB transfer function.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    ///
+    /// let c = Color::new(0.0, 0.5, 1.0).srgb2linear();
+    /// assert_eq!(c.r, 0.0);
+    /// assert!((c.g - 0.2140).abs() < 1e-4);
+    /// assert_eq!(c.b, 1.0);
+    /// ```
+    pub fn srgb2linear(&self) -> Self {
+        let decode = |c: f64| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        Color::new(decode(self.r), decode(self.g), decode(self.b))
+    }
+
     pub fn to_string_gamma(&self, samples_per_pixel: usize) -> String {
         let factor = 1.0 / samples_per_pixel as f64;
         self.linear2gamma(factor).to_string()
@@ -144,7 +220,7 @@ impl std::ops::Mul<Color> for Color {
     type Output = Color;
 
     fn mul(self, rhs: Color) -> Self::Output {
-        Color::new(self.r * rhs.r, self.g * rhs.r, self.b * rhs.r)
+        Color::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b)
     }
 }
 
@@ -160,7 +236,7 @@ impl std::ops::Mul<Color> for f64 {
     type Output = Color;
 
     fn mul(self, rhs: Color) -> Self::Output {
-        Color::new(self * rhs.r, self * rhs.r, self * rhs.r)
+        Color::new(self * rhs.r, self * rhs.g, self * rhs.b)
     }
 }
 
@@ -212,11 +288,11 @@ impl std::ops::DivAssign<f64> for Color {
     }
 }
 
-impl std::string::ToString for Color {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let ir = (self.r * 255.99) as i32;
         let ig = (self.g * 255.99) as i32;
         let ib = (self.b * 255.99) as i32;
-        format!("{} {} {}\n", ir, ig, ib)
+        writeln!(f, "{} {} {}", ir, ig, ib)
     }
 }
@@ -0,0 +1,330 @@
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// A trait for pixel reconstruction filter.
+///
+/// Each sample is splatted to the pixels whose centers are within `radius`,
+/// weighted by `eval` of the offset from the pixel center to the sample.
+pub trait Filter: Sync + Send + Debug {
+    /// Returns the radius of the support in pixels.
+    fn radius(&self) -> f64;
+
+    /// Returns the weight of the 1D offset, the 2D weight is the product of the weights of x and y.
+    ///
+    /// # Arguments
+    /// * `x`   - The offset in pixels.
+    fn eval_1d(&self, x: f64) -> f64;
+
+    /// Returns the weight of the 2D offset.
+    ///
+    /// # Arguments
+    /// * `x`   - The x offset in pixels.
+    /// * `y`   - The y offset in pixels.
+    fn eval(&self, x: f64, y: f64) -> f64 {
+        self.eval_1d(x) * self.eval_1d(y)
+    }
+}
+
+/// A box filter, which averages the samples inside the support with the same weight.
+///
+/// With the radius `0.5`, each sample contributes only to the pixel containing it.
+///
+/// # Examples
+/// ```
+/// use raytrs::filter::{BoxFilter, Filter};
+///
+/// let filter = BoxFilter::new(0.5);
+/// assert_eq!(filter.eval(0.5, 0.0), 1.0);
+/// assert_eq!(filter.eval(-0.5, 0.0), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BoxFilter {
+    radius: f64,
+}
+
+impl BoxFilter {
+    /// Constructs `BoxFilter`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels.
+    pub fn new(radius: f64) -> Self {
+        BoxFilter { radius }
+    }
+}
+
+impl Filter for BoxFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns `1.0` in the half-open range $(-r, r]$, so that a sample on the boundary is counted once.
+    fn eval_1d(&self, x: f64) -> f64 {
+        if -self.radius < x && x <= self.radius {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A tent filter, whose weight decreases linearly from the center.
+///
+/// # Examples
+/// ```
+/// use raytrs::filter::{Filter, TentFilter};
+///
+/// let filter = TentFilter::new(1.0);
+/// assert_eq!(filter.eval_1d(0.0), 1.0);
+/// assert_eq!(filter.eval_1d(0.5), 0.5);
+/// assert_eq!(filter.eval_1d(1.5), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TentFilter {
+    radius: f64,
+}
+
+impl TentFilter {
+    /// Constructs `TentFilter`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels.
+    pub fn new(radius: f64) -> Self {
+        TentFilter { radius }
+    }
+}
+
+impl Filter for TentFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn eval_1d(&self, x: f64) -> f64 {
+        (1.0 - x.abs() / self.radius).max(0.0)
+    }
+}
+
+/// A Gaussian filter, which is shifted to be zero at the radius.
+///
+/// # Examples
+/// ```
+/// use raytrs::filter::{Filter, GaussianFilter};
+///
+/// let filter = GaussianFilter::new(1.5, 0.5);
+/// assert!(filter.eval_1d(0.0) > filter.eval_1d(0.5));
+/// assert_eq!(filter.eval_1d(1.5), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GaussianFilter {
+    radius: f64,
+    sigma: f64,
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl GaussianFilter {
+    /// Constructs `GaussianFilter`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels.
+    /// * `sigma`   - The standard deviation in pixels.
+    pub fn new(radius: f64, sigma: f64) -> Self {
+        GaussianFilter { radius, sigma }
+    }
+
+    fn gaussian(&self, x: f64) -> f64 {
+        (-x * x / (2.0 * self.sigma * self.sigma)).exp()
+    }
+}
+
+impl Filter for GaussianFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns $g(x) - g(r)$, where $g$ is the Gaussian function.
+    fn eval_1d(&self, x: f64) -> f64 {
+        (self.gaussian(x) - self.gaussian(self.radius)).max(0.0)
+    }
+}
+
+/// A Mitchell-Netravali filter, which is a cubic filter with parameters `b` and `c`.
+///
+/// `b = c = 1/3` is recommended, which balances blurring and ringing.
+///
+/// # Examples
+/// ```
+/// use raytrs::filter::{Filter, MitchellFilter};
+///
+/// let filter = MitchellFilter::new(2.0, 1.0 / 3.0, 1.0 / 3.0);
+/// assert!((filter.eval_1d(0.0) - 8.0 / 9.0).abs() < 1e-12);
+/// assert!(filter.eval_1d(1.5) < 0.0);
+/// assert_eq!(filter.eval_1d(2.0), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MitchellFilter {
+    radius: f64,
+    b: f64,
+    c: f64,
+}
+
+impl MitchellFilter {
+    /// Constructs `MitchellFilter`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels, where the cubic is scaled from the range `[-2, 2]`.
+    /// * `b`       - The B parameter.
+    /// * `c`       - The C parameter.
+    pub fn new(radius: f64, b: f64, c: f64) -> Self {
+        MitchellFilter { radius, b, c }
+    }
+}
+
+impl Filter for MitchellFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn eval_1d(&self, x: f64) -> f64 {
+        let (b, c) = (self.b, self.c);
+        let x = (2.0 * x / self.radius).abs();
+        let v = if x < 1.0 {
+            (12.0 - 9.0 * b - 6.0 * c) * x.powi(3)
+                + (-18.0 + 12.0 * b + 6.0 * c) * x * x
+                + (6.0 - 2.0 * b)
+        } else if x < 2.0 {
+            (-b - 6.0 * c) * x.powi(3)
+                + (6.0 * b + 30.0 * c) * x * x
+                + (-12.0 * b - 48.0 * c) * x
+                + (8.0 * b + 24.0 * c)
+        } else {
+            0.0
+        };
+        v / 6.0
+    }
+}
+
+/// A Lanczos filter, which is the sinc function windowed by the wider sinc function.
+///
+/// # Examples
+/// ```
+/// use raytrs::filter::{Filter, LanczosFilter};
+///
+/// let filter = LanczosFilter::new(2.0);
+/// assert_eq!(filter.eval_1d(0.0), 1.0);
+/// assert!(filter.eval_1d(1.0).abs() < 1e-12);
+/// assert!(filter.eval_1d(1.5) < 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LanczosFilter {
+    radius: f64,
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl LanczosFilter {
+    /// Constructs `LanczosFilter`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels, which is also the number of lobes $a$.
+    pub fn new(radius: f64) -> Self {
+        LanczosFilter { radius }
+    }
+}
+
+impl Filter for LanczosFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns $\mathrm{sinc}(x)\mathrm{sinc}(x / a)$ for $|x| < a$.
+    fn eval_1d(&self, x: f64) -> f64 {
+        if x.abs() >= self.radius {
+            0.0
+        } else {
+            sinc(x) * sinc(x / self.radius)
+        }
+    }
+}
+
+/// Returns the normalized sinc function $\sin(\pi x) / \pi x$.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-8 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Represents the type of reconstruction filter.
+///
+/// # Variants
+/// * `Box`         - `BoxFilter`, whose default radius is `0.5`.
+/// * `Tent`        - `TentFilter`, whose default radius is `1.0`.
+/// * `Gaussian`    - `GaussianFilter`, whose default radius is `1.5` and sigma is a third of the radius.
+/// * `Mitchell`    - `MitchellFilter` with `b = c = 1/3`, whose default radius is `2.0`.
+/// * `Lanczos`     - `LanczosFilter`, whose default radius is `2.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterType {
+    #[default]
+    Box,
+    Tent,
+    Gaussian,
+    Mitchell,
+    Lanczos,
+}
+
+impl FilterType {
+    /// Returns the default radius of the filter.
+    pub fn default_radius(&self) -> f64 {
+        match self {
+            FilterType::Box => 0.5,
+            FilterType::Tent => 1.0,
+            FilterType::Gaussian => 1.5,
+            FilterType::Mitchell | FilterType::Lanczos => 2.0,
+        }
+    }
+
+    /// Constructs the filter.
+    ///
+    /// # Arguments
+    /// * `radius`  - The radius in pixels, or the default radius if `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::filter::FilterType;
+    ///
+    /// let filter = FilterType::Mitchell.build(None);
+    /// assert_eq!(filter.radius(), 2.0);
+    /// ```
+    pub fn build(&self, radius: Option<f64>) -> Box<dyn Filter> {
+        let radius = radius.unwrap_or_else(|| self.default_radius());
+        match self {
+            FilterType::Box => Box::new(BoxFilter::new(radius)),
+            FilterType::Tent => Box::new(TentFilter::new(radius)),
+            FilterType::Gaussian => Box::new(GaussianFilter::new(radius, radius / 3.0)),
+            FilterType::Mitchell => Box::new(MitchellFilter::new(radius, 1.0 / 3.0, 1.0 / 3.0)),
+            FilterType::Lanczos => Box::new(LanczosFilter::new(radius)),
+        }
+    }
+}
+
+impl FromStr for FilterType {
+    type Err = String;
+
+    /// Parses the filter type from `box`, `tent`, `gaussian`, `mitchell` or `lanczos`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "box" => Ok(FilterType::Box),
+            "tent" => Ok(FilterType::Tent),
+            "gaussian" => Ok(FilterType::Gaussian),
+            "mitchell" => Ok(FilterType::Mitchell),
+            "lanczos" => Ok(FilterType::Lanczos),
+            _ => Err(format!(
+                "unknown filter `{}`, expected one of box, tent, gaussian, mitchell, lanczos",
+                s
+            )),
+        }
+    }
+}
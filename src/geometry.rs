@@ -1,5 +1,7 @@
+use self::matrix::Mat4 as _Mat4;
 use self::vec3::Vec3 as _Vec3;
 
+pub(crate) mod matrix;
 pub(crate) mod vec3;
 
 /// An alias of `Vec3`
@@ -7,3 +9,6 @@ pub type Point3 = Vec3;
 
 /// A shortcut of `raytrs::geometry::vec3::Vec3`
 pub type Vec3 = _Vec3;
+
+/// A shortcut of `raytrs::geometry::matrix::Mat4`
+pub type Mat4 = _Mat4;
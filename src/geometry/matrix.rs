@@ -0,0 +1,178 @@
+use crate::geometry::vec3::Vec3;
+
+/// A 4x4 matrix to represent affine transformation.
+///
+/// The elements are stored in row-major order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    /// The identity matrix.
+    pub const IDENTITY: Mat4 = Mat4 {
+        m: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    };
+
+    /// Constructs `Mat4` from rows.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::Mat4;
+    ///
+    /// let m = Mat4::from_rows([
+    ///     [1.0, 0.0, 0.0, 0.0],
+    ///     [0.0, 1.0, 0.0, 0.0],
+    ///     [0.0, 0.0, 1.0, 0.0],
+    ///     [0.0, 0.0, 0.0, 1.0],
+    /// ]);
+    /// assert_eq!(m, Mat4::IDENTITY);
+    /// ```
+    pub fn from_rows(m: [[f64; 4]; 4]) -> Self {
+        Mat4 { m }
+    }
+
+    /// Constructs `Mat4` from columns, which is the layout used by glTF and OpenGL.
+    pub fn from_columns(c: [[f64; 4]; 4]) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, e) in row.iter_mut().enumerate() {
+                *e = c[j][i];
+            }
+        }
+        Mat4 { m }
+    }
+
+    /// Constructs translation matrix.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::{Mat4, Vec3};
+    ///
+    /// let m = Mat4::translation(Vec3::new(1.0, 2.0, 3.0));
+    /// assert_eq!(m.transform_point(Vec3::zeros()), Vec3::new(1.0, 2.0, 3.0));
+    /// assert_eq!(m.transform_vector(Vec3::ones()), Vec3::ones());
+    /// ```
+    pub fn translation(t: Vec3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.m[0][3] = t[0];
+        m.m[1][3] = t[1];
+        m.m[2][3] = t[2];
+        m
+    }
+
+    /// Constructs scaling matrix.
+    pub fn scaling(s: Vec3) -> Self {
+        let mut m = Self::IDENTITY;
+        m.m[0][0] = s[0];
+        m.m[1][1] = s[1];
+        m.m[2][2] = s[2];
+        m
+    }
+
+    /// Constructs rotation matrix around the unit `axis` by `angle` \[rad\].
+    pub fn rotation(axis: Vec3, angle: f64) -> Self {
+        let a = axis.as_unit();
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        let (x, y, z) = (a[0], a[1], a[2]);
+        Mat4::from_rows([
+            [t * x * x + c, t * x * y - s * z, t * x * z + s * y, 0.0],
+            [t * x * y + s * z, t * y * y + c, t * y * z - s * x, 0.0],
+            [t * x * z - s * y, t * y * z + s * x, t * z * z + c, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Returns the element at `row` and `col`.
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.m[row][col]
+    }
+
+    /// Returns the transformed point, which is affected by translation.
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * p[0] + m[0][1] * p[1] + m[0][2] * p[2] + m[0][3],
+            m[1][0] * p[0] + m[1][1] * p[1] + m[1][2] * p[2] + m[1][3],
+            m[2][0] * p[0] + m[2][1] * p[1] + m[2][2] * p[2] + m[2][3],
+        )
+    }
+
+    /// Returns the transformed vector, which is not affected by translation.
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+            m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+            m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+        )
+    }
+
+    /// Returns the transformed normal with the inverse transpose of the upper-left 3x3 matrix.
+    ///
+    /// The returned vector is normalized.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::{Mat4, Vec3};
+    ///
+    /// let m = Mat4::scaling(Vec3::new(2.0, 1.0, 1.0));
+    /// let n = m.transform_normal(Vec3::new(1.0, 1.0, 0.0));
+    /// assert!((n - Vec3::new(1.0, 2.0, 0.0).as_unit()).is_close(0.0));
+    /// ```
+    pub fn transform_normal(&self, n: Vec3) -> Vec3 {
+        // The cofactor matrix equals the inverse transpose up to the scale of determinant.
+        let m = &self.m;
+        let c = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+        let cof = [
+            [c(1, 2, 1, 2), -c(1, 2, 0, 2), c(1, 2, 0, 1)],
+            [-c(0, 2, 1, 2), c(0, 2, 0, 2), -c(0, 2, 0, 1)],
+            [c(0, 1, 1, 2), -c(0, 1, 0, 2), c(0, 1, 0, 1)],
+        ];
+        let det = m[0][0] * cof[0][0] + m[0][1] * cof[0][1] + m[0][2] * cof[0][2];
+        let v = Vec3::new(
+            cof[0][0] * n[0] + cof[0][1] * n[1] + cof[0][2] * n[2],
+            cof[1][0] * n[0] + cof[1][1] * n[1] + cof[1][2] * n[2],
+            cof[2][0] * n[0] + cof[2][1] * n[1] + cof[2][2] * n[2],
+        );
+        (v * det.signum()).as_unit()
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// The multiplication operator `Mat4 * Mat4`.
+///
+/// # Example
+/// ```
+/// use raytrs::geometry::{Mat4, Vec3};
+///
+/// let t = Mat4::translation(Vec3::new(1.0, 0.0, 0.0));
+/// let s = Mat4::scaling(Vec3::new(2.0, 2.0, 2.0));
+/// assert_eq!((t * s).transform_point(Vec3::ones()), Vec3::new(3.0, 2.0, 2.0));
+/// ```
+impl std::ops::Mul<Mat4> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, e) in row.iter_mut().enumerate() {
+                *e = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
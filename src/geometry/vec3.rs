@@ -1,4 +1,4 @@
-use rand::Rng;
+use crate::utils::{random, Rng};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec3 {
@@ -47,18 +47,35 @@ impl Vec3 {
         Vec3 { e }
     }
 
+    /// Returns Vec3 filled by the specified value.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    ///
+    /// let v = Vec3::splat(2.0);
+    /// assert_eq!(v, Vec3::new(2.0, 2.0, 2.0));
+    /// ```
+    pub const fn splat(x: f64) -> Self {
+        Vec3 { e: [x, x, x] }
+    }
+
     /// Returns Vec3 build from random values.
     ///
+    /// # Arguments
+    /// * `rng` - The random generator.
+    ///
     /// # Examples
     /// ```
     /// use raytrs::geometry::Vec3;
+    /// use raytrs::utils::Rng;
     ///
-    /// let v = Vec3::rand();
+    /// let v = Vec3::rand(&mut Rng::new(0));
     /// ```
-    pub fn rand() -> Self {
-        let mut rng = rand::thread_rng();
-        let e = [rng.gen(), rng.gen(), rng.gen()];
-        Vec3 { e }
+    pub fn rand(rng: &mut Rng) -> Self {
+        Vec3 {
+            e: [random(rng), random(rng), random(rng)],
+        }
     }
 
     /// Returns x value reference.
@@ -181,17 +198,41 @@ impl Vec3 {
         *self / self.norm()
     }
 
-    /// Returns random Vec3 as unit vector.
+    /// Returns random Vec3 as unit vector, which is uniformly distributed on the unit sphere.
+    ///
+    /// Normalizing a point in the cube would crowd directions toward its corners,
+    /// so the point is rejected unless it is in the unit ball, whose directions are uniform.
+    /// Diffuse scattering relies on the uniformity to follow the cosine distribution.
+    ///
+    /// # Arguments
+    /// * `rng` - The random generator.
     ///
     /// # Example
     /// ```
     /// use raytrs::geometry::Vec3;
+    /// use raytrs::utils::Rng;
     ///
-    /// let v = Vec3::rand_unit();
+    /// let v = Vec3::rand_unit(&mut Rng::new(0));
     /// assert!(v.norm() <= 1.0);
     /// ```
-    pub fn rand_unit() -> Self {
-        Vec3::rand().as_unit()
+    ///
+    /// Every direction is equally likely, so the mean of many samples is close to zero.
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::utils::Rng;
+    ///
+    /// let mut rng = Rng::new(0);
+    /// let mean = (0..10000).fold(Vec3::zeros(), |acc, _| acc + Vec3::rand_unit(&mut rng)) / 10000.0;
+    /// assert!(mean.norm() < 0.05);
+    /// ```
+    pub fn rand_unit(rng: &mut Rng) -> Self {
+        loop {
+            let v = Vec3::rand(rng) * 2.0 - 1.0;
+            let n = v.norm_squared();
+            if 1e-12 < n && n <= 1.0 {
+                return v / n.sqrt();
+            }
+        }
     }
 
     /// Returns whether elements are close to specified value.
@@ -205,9 +246,43 @@ impl Vec3 {
     /// ```
     pub fn is_close(&self, x: f64) -> bool {
         const TOLERANCE: f64 = 1e-8;
-        return (self.e[0] - x).abs() < TOLERANCE
+        (self.e[0] - x).abs() < TOLERANCE
             && (self.e[1] - x).abs() < TOLERANCE
-            && (self.e[2] - x).abs() < TOLERANCE;
+            && (self.e[2] - x).abs() < TOLERANCE
+    }
+
+    /// Returns the component-wise minimum.
+    ///
+    /// # Example
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    ///
+    /// let v = Vec3::new(1.0, 5.0, 3.0).min(Vec3::new(2.0, 4.0, 3.0));
+    /// assert_eq!(v, Vec3::new(1.0, 4.0, 3.0));
+    /// ```
+    pub fn min(&self, rhs: Vec3) -> Vec3 {
+        Vec3::new(
+            self.e[0].min(rhs.e[0]),
+            self.e[1].min(rhs.e[1]),
+            self.e[2].min(rhs.e[2]),
+        )
+    }
+
+    /// Returns the component-wise maximum.
+    ///
+    /// # Example
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    ///
+    /// let v = Vec3::new(1.0, 5.0, 3.0).max(Vec3::new(2.0, 4.0, 3.0));
+    /// assert_eq!(v, Vec3::new(2.0, 5.0, 3.0));
+    /// ```
+    pub fn max(&self, rhs: Vec3) -> Vec3 {
+        Vec3::new(
+            self.e[0].max(rhs.e[0]),
+            self.e[1].max(rhs.e[1]),
+            self.e[2].max(rhs.e[2]),
+        )
     }
 
     pub fn iter(&self) -> std::slice::Iter<'_, f64> {
@@ -569,6 +644,23 @@ impl std::ops::DivAssign<f64> for Vec3 {
     }
 }
 
+/// The index operator `Vec3[i]`.
+///
+/// # Example
+/// ```
+/// use raytrs::geometry::Vec3;
+///
+/// let v = Vec3::new(1.0, 2.0, 3.0);
+/// assert_eq!(v[1], 2.0);
+/// ```
+impl std::ops::Index<usize> for Vec3 {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.e[index]
+    }
+}
+
 /// Performs the unary `-` operation.
 ///
 /// # Examples
@@ -650,6 +742,21 @@ pub(crate) fn refract(v: Vec3, n: Vec3, ratio: f64) -> Option<Vec3> {
     }
 }
 
+/// Returns two unit vectors which form an orthonormal basis with the unit vector `n`.
+///
+/// This is the branchless construction by Duff et al. (2017).
+///
+/// # Arguments
+/// * `n`   - A unit vector.
+pub(crate) fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+    let sign = 1.0_f64.copysign(*n.z());
+    let a = -1.0 / (sign + n.z());
+    let b = n.x() * n.y() * a;
+    let t = Vec3::new(1.0 + sign * n.x() * n.x() * a, sign * b, -sign * n.x());
+    let s = Vec3::new(b, sign + n.y() * n.y() * a, -n.y());
+    (t, s)
+}
+
 fn _dot(v1: &[f64; 3], v2: &[f64; 3]) -> f64 {
     v1[0] * v2[0] + v1[1] * v2[1] + v1[2] * v2[2]
 }
@@ -1,10 +1,16 @@
 /// A struct to represent min/max range of the ray interval.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Interval {
     pub(crate) min: f64,
     pub(crate) max: f64,
 }
 
+impl Default for Interval {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Interval {
     pub const EMPTY: Interval = Interval {
         min: f64::INFINITY,
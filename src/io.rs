@@ -0,0 +1,75 @@
+pub(crate) mod exr;
+pub(crate) mod gltf;
+pub(crate) mod ply;
+pub(crate) mod scene;
+pub(crate) mod stl;
+
+use std::fmt;
+
+pub use self::exr::save_exr;
+pub use self::gltf::{load_gltf, read_gltf};
+pub use self::ply::{load_ply, read_ply};
+pub use self::scene::{load_scene, read_scene, RenderSettings, SceneFile, SceneFormat};
+pub use self::stl::{load_stl, read_stl};
+
+/// An error occurred while importing or exporting files.
+///
+/// # Variants
+/// * `Io`      - Failed to read or write the file.
+/// * `Gltf`    - Failed to import glTF.
+/// * `Exr`     - Failed to export OpenEXR.
+/// * `Format`  - The file content is invalid.
+/// * `Scene`   - The scene description is invalid at `path`.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Gltf(::gltf::Error),
+    Exr(::exr::error::Error),
+    Format(String),
+    Scene { path: String, message: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Gltf(e) => write!(f, "glTF error: {}", e),
+            Error::Exr(e) => write!(f, "OpenEXR error: {}", e),
+            Error::Format(msg) => write!(f, "format error: {}", msg),
+            Error::Scene { path, message } if path.is_empty() => write!(f, "{}", message),
+            Error::Scene { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Gltf(e) => Some(e),
+            Error::Exr(e) => Some(e),
+            Error::Format(_) | Error::Scene { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<::gltf::Error> for Error {
+    fn from(e: ::gltf::Error) -> Self {
+        Error::Gltf(e)
+    }
+}
+
+impl From<::exr::error::Error> for Error {
+    fn from(e: ::exr::error::Error) -> Self {
+        Error::Exr(e)
+    }
+}
+
+/// A shortcut of `Result` with `raytrs::io::Error`.
+pub type Result<T> = std::result::Result<T, Error>;
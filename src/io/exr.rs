@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use exr::prelude::*;
+
+use crate::render::{Aov, Film};
+
+/// Saves the film into OpenEXR of 32-bit floats.
+///
+/// The image is written to `R`, `G` and `B` channels, and each AOV is written as a layer of channels
+/// prefixed with its name, e.g. `normal.X`, following the convention of multi-layer OpenEXR.
+///
+/// # Arguments
+/// * `path`    - The output path.
+/// * `film`    - The rendered film.
+/// * `aovs`    - The AOVs to write, which requires `film` to be rendered with AOVs.
+pub fn save_exr<P>(path: P, film: &Film, aovs: &[Aov]) -> crate::io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let (width, height) = (film.width(), film.height());
+    let pixels = || (0..height).flat_map(|y| (0..width).map(move |x| (x, y)));
+
+    let mut channels = ["R", "G", "B"]
+        .iter()
+        .enumerate()
+        .map(|(c, &name)| {
+            let samples = pixels()
+                .map(|(x, y)| {
+                    let color = film.get(x, y);
+                    [color.r, color.g, color.b][c] as f32
+                })
+                .collect();
+            AnyChannel::new(name, FlatSamples::F32(samples))
+        })
+        .collect::<Vec<_>>();
+
+    if !aovs.is_empty() {
+        let buffer = film.aovs().ok_or_else(|| {
+            crate::io::Error::Format("the film is rendered without AOVs".to_string())
+        })?;
+        for aov in aovs {
+            let values = pixels()
+                .map(|(x, y)| buffer.get(*aov, x, y))
+                .collect::<Vec<_>>();
+            for (c, name) in aov.channels().iter().enumerate() {
+                let samples = values.iter().map(|v| v[c] as f32).collect();
+                channels.push(AnyChannel::new(
+                    format!("{}.{}", aov.name(), name).as_str(),
+                    FlatSamples::F32(samples),
+                ));
+            }
+        }
+    }
+
+    let layer = Layer::new(
+        (width as usize, height as usize),
+        LayerAttributes::default(),
+        Encoding::FAST_LOSSLESS,
+        AnyChannels::sort(channels.into()),
+    );
+    Image::from_layer(layer).write().to_file(path)?;
+    Ok(())
+}
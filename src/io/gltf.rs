@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use gltf::camera::Projection;
+use gltf::image::Format;
+use gltf::mesh::Mode;
+use gltf::texture::WrappingMode;
+use gltf::{Document, Node};
+
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::geometry::{Mat4, Vec3};
+use crate::io::{Error, Result};
+use crate::material::{ColorTexture, ImageTexture, Material, MetallicRoughness};
+use crate::material::{ScaleTexture, Texture};
+use crate::render::Scene;
+use crate::shape::{Aabb, Mesh, TriangleMesh};
+
+/// The vertical FOV \[deg\] of the camera used if the file has no camera.
+const DEFAULT_VFOV: f64 = 45.0;
+
+/// Reads glTF 2.0 from bytes of `.gltf` or `.glb`.
+///
+/// Buffers and images must be embedded, use `load_gltf` to resolve external files.
+///
+/// # Arguments
+/// * `bytes`   - The content of `.gltf` or `.glb`.
+/// * `width`   - The image width.
+/// * `height`  - The image height.
+///
+/// # Examples
+/// ```
+/// use raytrs::io::read_gltf;
+/// use raytrs::render::Renderer;
+///
+/// let gltf = r#"{
+///     "asset": { "version": "2.0" },
+///     "scene": 0,
+///     "scenes": [{ "nodes": [0, 1] }],
+///     "nodes": [
+///         { "mesh": 0 },
+///         { "camera": 0, "translation": [0.0, 0.0, 3.0] }
+///     ],
+///     "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.8, "znear": 0.1 } }],
+///     "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+///     "accessors": [{
+///         "bufferView": 0,
+///         "componentType": 5126,
+///         "count": 3,
+///         "type": "VEC3",
+///         "min": [0.0, 0.0, 0.0],
+///         "max": [1.0, 1.0, 0.0]
+///     }],
+///     "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+///     "buffers": [{
+///         "byteLength": 36,
+///         "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"
+///     }]
+/// }"#;
+/// let scene = read_gltf(gltf.as_bytes(), 64, 48).unwrap();
+/// assert_eq!(scene.camera().origin, raytrs::geometry::Vec3::new(0.0, 0.0, 3.0));
+/// ```
+pub fn read_gltf(bytes: &[u8], width: u32, height: u32) -> Result<Scene> {
+    let (document, buffers, images) = gltf::import_slice(bytes)?;
+    build_scene(&document, &buffers, &images, width, height)
+}
+
+/// Loads glTF 2.0 file of `.gltf` or `.glb`.
+///
+/// The node hierarchy is flattened by baking the world transforms into meshes.
+/// Triangle strips and fans are triangulated, while points and lines are errors.
+/// Materials are imported as `MetallicRoughness` with base color, metallic-roughness, normal and emissive textures.
+/// The first perspective camera is used if present,
+/// otherwise the camera looks at the whole scene from `+z`.
+///
+/// # Arguments
+/// * `path`    - The path of glTF file.
+/// * `width`   - The image width.
+/// * `height`  - The image height.
+///
+/// # Examples
+/// ```
+/// use raytrs::io::load_gltf;
+/// use raytrs::render::Renderer;
+///
+/// // A triangle lit only by its grey and alpha emissive texture.
+/// let dir = std::env::temp_dir().join("raytrs_load_gltf");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// image::GrayAlphaImage::from_pixel(2, 2, image::LumaA([128, 255]))
+///     .save(dir.join("emissive.png"))
+///     .unwrap();
+/// let gltf = r#"{
+///     "asset": { "version": "2.0" },
+///     "scenes": [{ "nodes": [0, 1] }],
+///     "nodes": [
+///         { "mesh": 0 },
+///         { "camera": 0, "translation": [0.0, 0.0, 3.0] }
+///     ],
+///     "cameras": [{ "type": "perspective", "perspective": { "yfov": 0.8, "znear": 0.1 } }],
+///     "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "material": 0 }] }],
+///     "materials": [{
+///         "pbrMetallicRoughness": { "baseColorFactor": [0.0, 0.0, 0.0, 1.0], "metallicFactor": 0.0 },
+///         "emissiveFactor": [1.0, 1.0, 1.0],
+///         "emissiveTexture": { "index": 0 }
+///     }],
+///     "textures": [{ "source": 0 }],
+///     "images": [{ "uri": "emissive.png" }],
+///     "accessors": [{
+///         "bufferView": 0,
+///         "componentType": 5126,
+///         "count": 3,
+///         "type": "VEC3",
+///         "min": [-4.0, -4.0, 0.0],
+///         "max": [4.0, 4.0, 0.0]
+///     }],
+///     "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+///     "buffers": [{
+///         "byteLength": 36,
+///         "uri": "data:application/octet-stream;base64,AACAwAAAgMAAAAAAAACAQAAAgMAAAAAAAAAAAAAAgEAAAAAA"
+///     }]
+/// }"#;
+/// std::fs::write(dir.join("scene.gltf"), gltf).unwrap();
+///
+/// let scene = load_gltf(dir.join("scene.gltf"), 4, 4).unwrap();
+/// // Grey and alpha images are grey, whose alpha is not a color channel.
+/// let [r, g, b] = scene.render_basic().get_pixel(2, 2).0;
+/// assert!(r > 0 && r == g && g == b);
+/// ```
+pub fn load_gltf<P>(path: P, width: u32, height: u32) -> Result<Scene>
+where
+    P: AsRef<Path>,
+{
+    let (document, buffers, images) = gltf::import(path)?;
+    build_scene(&document, &buffers, &images, width, height)
+}
+
+/// A imported primitive before being converted into `TriangleMesh`.
+struct Primitive {
+    mesh: Mesh,
+    material: Option<usize>,
+}
+
+/// A container to convert glTF materials and textures into `raytrs` ones.
+struct Importer<'a> {
+    document: &'a Document,
+    images: &'a [gltf::image::Data],
+    // The textures keyed by their indices and whether they are decoded from sRGB.
+    textures: HashMap<(usize, bool), Arc<dyn Texture>>,
+}
+
+fn build_scene(
+    document: &Document,
+    buffers: &[gltf::buffer::Data],
+    images: &[gltf::image::Data],
+    width: u32,
+    height: u32,
+) -> Result<Scene> {
+    let gltf_scene = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+        .ok_or_else(|| Error::Format("no scene".to_string()))?;
+
+    let mut primitives = Vec::new();
+    let mut camera = None;
+    for node in gltf_scene.nodes() {
+        visit(
+            &node,
+            Mat4::IDENTITY,
+            buffers,
+            &mut primitives,
+            &mut camera,
+            width,
+            height,
+        )?;
+    }
+
+    let camera = match camera {
+        Some(camera) => camera,
+        None => default_camera(&primitives, width, height),
+    };
+
+    let mut importer = Importer {
+        document,
+        images,
+        textures: HashMap::new(),
+    };
+    let mut materials = HashMap::new();
+    let mut scene = Scene::new(camera);
+    for primitive in primitives {
+        let (material, normal_map) = materials
+            .entry(primitive.material)
+            .or_insert_with(|| importer.material(primitive.material))
+            .clone();
+        let shape = match normal_map {
+            Some((texture, scale)) if primitive.mesh.has_uvs() => {
+                TriangleMesh::with_normal_map(&primitive.mesh, material, texture, scale)
+            }
+            _ => TriangleMesh::new(&primitive.mesh, material),
+        };
+        scene.push(Box::new(shape));
+    }
+    Ok(scene)
+}
+
+/// Visits nodes recursively to collect primitives and the first camera in the world coordinates.
+fn visit(
+    node: &Node,
+    parent: Mat4,
+    buffers: &[gltf::buffer::Data],
+    primitives: &mut Vec<Primitive>,
+    camera: &mut Option<Camera>,
+    width: u32,
+    height: u32,
+) -> Result<()> {
+    let local = node.transform().matrix().map(|c| c.map(|e| e as f64));
+    let world = parent * Mat4::from_columns(local);
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+            let positions = reader
+                .read_positions()
+                .ok_or_else(|| Error::Format("primitive without POSITION".to_string()))?;
+
+            let mut m = Mesh::new();
+            m.positions = positions
+                .map(|p| world.transform_point(Vec3::new(p[0] as f64, p[1] as f64, p[2] as f64)))
+                .collect();
+            if let Some(normals) = reader.read_normals() {
+                m.normals = normals
+                    .map(|n| {
+                        world.transform_normal(Vec3::new(n[0] as f64, n[1] as f64, n[2] as f64))
+                    })
+                    .collect();
+            }
+            if let Some(uvs) = reader.read_tex_coords(0) {
+                m.uvs = uvs
+                    .into_f32()
+                    .map(|uv| (uv[0] as f64, uv[1] as f64))
+                    .collect();
+            }
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..m.positions.len()).collect(),
+            };
+            if indices.iter().any(|&i| i >= m.positions.len()) {
+                return Err(Error::Format("vertex index out of range".to_string()));
+            }
+            m.faces = faces(primitive.mode(), &indices)?;
+
+            primitives.push(Primitive {
+                mesh: m,
+                material: primitive.material().index(),
+            });
+        }
+    }
+
+    if let (None, Some(gltf_camera)) = (&camera, node.camera()) {
+        if let Projection::Perspective(perspective) = gltf_camera.projection() {
+            // glTF cameras look at -z with +y up in their local coordinates.
+            let origin = world.transform_point(Vec3::zeros());
+            let forward = world.transform_vector(Vec3::new(0.0, 0.0, -1.0));
+            let up = world.transform_vector(Vec3::new(0.0, 1.0, 0.0));
+            *camera = Some(Camera::from_lookat(
+                origin,
+                origin + forward,
+                up.as_unit(),
+                (perspective.yfov() as f64).to_degrees(),
+                width,
+                height,
+            ));
+        }
+    }
+
+    for child in node.children() {
+        visit(&child, world, buffers, primitives, camera, width, height)?;
+    }
+    Ok(())
+}
+
+/// Returns the triangles of the primitive drawn in `mode` with vertex `indices`.
+///
+/// Strips and fans are triangulated keeping the winding order defined by glTF,
+/// while points and lines have no surface to render, which are rejected.
+fn faces(mode: Mode, indices: &[usize]) -> Result<Vec<[usize; 3]>> {
+    let n = indices.len();
+    match mode {
+        Mode::Triangles => Ok(indices
+            .chunks_exact(3)
+            .map(|f| [f[0], f[1], f[2]])
+            .collect()),
+        Mode::TriangleStrip => Ok((0..n.saturating_sub(2))
+            .map(|i| {
+                if i % 2 == 0 {
+                    [indices[i], indices[i + 1], indices[i + 2]]
+                } else {
+                    [indices[i], indices[i + 2], indices[i + 1]]
+                }
+            })
+            .collect()),
+        Mode::TriangleFan => Ok((0..n.saturating_sub(2))
+            .map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+            .collect()),
+        Mode::Points | Mode::Lines | Mode::LineLoop | Mode::LineStrip => {
+            Err(Error::Format(format!(
+                "unsupported primitive mode {:?}, expected triangles, triangle strip or triangle fan",
+                mode
+            )))
+        }
+    }
+}
+
+/// Returns the camera looking at the whole primitives from `+z`.
+fn default_camera(primitives: &[Primitive], width: u32, height: u32) -> Camera {
+    let bbox = primitives
+        .iter()
+        .flat_map(|p| p.mesh.positions.iter())
+        .fold(Aabb::EMPTY, |acc, p| acc.grow(*p));
+    let (center, radius) = if bbox.surface_area() > 0.0 {
+        (bbox.centroid(), (bbox.max - bbox.min).norm() * 0.5)
+    } else {
+        (Vec3::zeros(), 1.0)
+    };
+    let distance = radius / (DEFAULT_VFOV.to_radians() * 0.5).sin();
+    Camera::from_lookat(
+        center + Vec3::new(0.0, 0.0, distance),
+        center,
+        Vec3::new(0.0, 1.0, 0.0),
+        DEFAULT_VFOV,
+        width,
+        height,
+    )
+}
+
+impl<'a> Importer<'a> {
+    /// Returns the material and the optional normal map of the material `index`.
+    #[allow(clippy::type_complexity)]
+    fn material(
+        &mut self,
+        index: Option<usize>,
+    ) -> (Arc<dyn Material>, Option<(Arc<dyn Texture>, f64)>) {
+        let Some(material) = index.and_then(|i| self.document.materials().nth(i)) else {
+            let default =
+                MetallicRoughness::new(Box::new(ColorTexture::new(Color::WHITE)), 1.0, 1.0);
+            return (Arc::new(default), None);
+        };
+
+        let pbr = material.pbr_metallic_roughness();
+        let [r, g, b, _] = pbr.base_color_factor();
+        let factor = Color::new(r as f64, g as f64, b as f64);
+        let base_color: Box<dyn Texture> = match pbr.base_color_texture() {
+            Some(info) => Box::new(ScaleTexture::new(
+                Box::new(self.texture(&info.texture(), true)),
+                factor,
+            )),
+            None => Box::new(ColorTexture::new(factor)),
+        };
+
+        let mut m = MetallicRoughness::new(
+            base_color,
+            pbr.metallic_factor() as f64,
+            pbr.roughness_factor() as f64,
+        );
+        if let Some(info) = pbr.metallic_roughness_texture() {
+            m = m.with_metallic_roughness(Box::new(self.texture(&info.texture(), false)));
+        }
+
+        let [r, g, b] = material.emissive_factor();
+        let factor = Color::new(r as f64, g as f64, b as f64);
+        match material.emissive_texture() {
+            Some(info) => {
+                m = m.with_emissive(Box::new(ScaleTexture::new(
+                    Box::new(self.texture(&info.texture(), true)),
+                    factor,
+                )));
+            }
+            None if factor.r > 0.0 || factor.g > 0.0 || factor.b > 0.0 => {
+                m = m.with_emissive(Box::new(ColorTexture::new(factor)));
+            }
+            None => {}
+        }
+
+        let normal_map = material
+            .normal_texture()
+            .map(|info| (self.texture(&info.texture(), false), info.scale() as f64));
+        (Arc::new(m), normal_map)
+    }
+
+    /// Returns the texture shared among materials.
+    ///
+    /// Base color and emissive textures are sRGB-encoded, which are decoded into linear values if `srgb`.
+    /// The other textures such as metallic-roughness and normal maps are already linear.
+    fn texture(&mut self, texture: &gltf::Texture, srgb: bool) -> Arc<dyn Texture> {
+        let images = self.images;
+        let key = (texture.index(), srgb);
+        Arc::clone(self.textures.entry(key).or_insert_with(|| {
+            let data = &images[texture.source().index()];
+            let image = ImageTexture::from_pixels(
+                pixels(data, srgb),
+                data.width as usize,
+                data.height as usize,
+            );
+            let sampler = texture.sampler();
+            if sampler.wrap_s() == WrappingMode::ClampToEdge
+                && sampler.wrap_t() == WrappingMode::ClampToEdge
+            {
+                Arc::new(image)
+            } else {
+                Arc::new(image.repeat())
+            }
+        }))
+    }
+}
+
+/// Converts decoded image data into colors in `[0.0, 1.0]`,
+/// where 8-bit and 16-bit images are decoded from sRGB if `srgb`.
+///
+/// Floating-point images are linear regardless of `srgb`.
+/// Two-channel images are grey and alpha, whose alpha is ignored.
+fn pixels(data: &gltf::image::Data, srgb: bool) -> Vec<Color> {
+    let (channels, size) = match data.format {
+        Format::R8 => (1, 1),
+        Format::R8G8 => (2, 1),
+        Format::R8G8B8 => (3, 1),
+        Format::R8G8B8A8 => (4, 1),
+        Format::R16 => (1, 2),
+        Format::R16G16 => (2, 2),
+        Format::R16G16B16 => (3, 2),
+        Format::R16G16B16A16 => (4, 2),
+        Format::R32G32B32FLOAT => (3, 4),
+        Format::R32G32B32A32FLOAT => (4, 4),
+    };
+    let channel = |bytes: &[u8]| match size {
+        1 => bytes[0] as f64 / u8::MAX as f64,
+        2 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64 / u16::MAX as f64,
+        _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+    };
+    data.pixels
+        .chunks_exact(channels * size)
+        .map(|px| {
+            let c = px.chunks_exact(size).map(channel).collect::<Vec<_>>();
+            let color = match channels {
+                1 | 2 => Color::new(c[0], c[0], c[0]),
+                _ => Color::new(c[0], c[1], c[2]),
+            };
+            if srgb && size < 4 {
+                color.srgb2linear()
+            } else {
+                color
+            }
+        })
+        .collect()
+}
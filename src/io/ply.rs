@@ -0,0 +1,181 @@
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use ply_rs::parser::Parser;
+use ply_rs::ply::{DefaultElement, Property};
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::io::{Error, Result};
+use crate::shape::Mesh;
+
+/// Reads ASCII or binary PLY from `reader`.
+///
+/// The `vertex` element must have `x`, `y`, `z` properties,
+/// and optionally `nx`, `ny`, `nz` for normals, `red`, `green`, `blue` for colors and `u`, `v` (or `s`, `t`) for texture coordinates.
+/// Polygons of the `face` element are triangulated as triangle fan.
+///
+/// # Arguments
+/// * `reader`  - A reader of PLY.
+///
+/// # Examples
+/// ```
+/// use raytrs::io::read_ply;
+///
+/// let ply = "ply
+/// format ascii 1.0
+/// element vertex 3
+/// property float x
+/// property float y
+/// property float z
+/// property uchar red
+/// property uchar green
+/// property uchar blue
+/// element face 1
+/// property list uchar int vertex_indices
+/// end_header
+/// 0 0 0 255 0 0
+/// 1 0 0 0 255 0
+/// 0 1 0 0 0 255
+/// 3 0 1 2
+/// ";
+/// let mesh = read_ply(&mut ply.as_bytes()).unwrap();
+/// assert_eq!(mesh.positions.len(), 3);
+/// assert_eq!(mesh.colors[0].to_rgb(), [255, 0, 0]);
+/// assert_eq!(mesh.faces, vec![[0, 1, 2]]);
+/// ```
+pub fn read_ply<R>(reader: &mut R) -> Result<Mesh>
+where
+    R: Read,
+{
+    let parser = Parser::<DefaultElement>::new();
+    let ply = parser.read_ply(reader)?;
+
+    let mut mesh = Mesh::new();
+    let vertices = ply
+        .payload
+        .get("vertex")
+        .ok_or_else(|| Error::Format("missing `vertex` element".to_string()))?;
+
+    for vertex in vertices {
+        let position = vec3(vertex, ["x", "y", "z"])
+            .ok_or_else(|| Error::Format("vertex without `x`, `y`, `z`".to_string()))?;
+        mesh.positions.push(position);
+        if let Some(n) = vec3(vertex, ["nx", "ny", "nz"]) {
+            mesh.normals.push(n);
+        }
+        if let Some(c) = color(vertex, ["red", "green", "blue"])
+            .or_else(|| color(vertex, ["r", "g", "b"]))
+            .or_else(|| color(vertex, ["diffuse_red", "diffuse_green", "diffuse_blue"]))
+        {
+            mesh.colors.push(c);
+        }
+        if let Some(uv) = uv(vertex, ["u", "v"])
+            .or_else(|| uv(vertex, ["s", "t"]))
+            .or_else(|| uv(vertex, ["texture_u", "texture_v"]))
+        {
+            mesh.uvs.push(uv);
+        }
+    }
+
+    let n = mesh.positions.len();
+    if ![mesh.normals.len(), mesh.colors.len(), mesh.uvs.len()]
+        .iter()
+        .all(|&len| len == 0 || len == n)
+    {
+        return Err(Error::Format(
+            "vertex properties are partially defined".to_string(),
+        ));
+    }
+
+    if let Some(faces) = ply.payload.get("face") {
+        for face in faces {
+            let indices = face
+                .get("vertex_indices")
+                .or_else(|| face.get("vertex_index"))
+                .and_then(indices)
+                .ok_or_else(|| Error::Format("face without `vertex_indices`".to_string()))?;
+            if indices.iter().any(|&i| i >= n) {
+                return Err(Error::Format("vertex index out of range".to_string()));
+            }
+            mesh.push_polygon(&indices);
+        }
+    }
+    Ok(mesh)
+}
+
+/// Loads ASCII or binary PLY file.
+///
+/// # Arguments
+/// * `path`    - The path of PLY file.
+pub fn load_ply<P>(path: P) -> Result<Mesh>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+    read_ply(&mut reader)
+}
+
+/// Returns the scalar property as `f64`.
+fn scalar(property: &Property) -> Option<f64> {
+    match property {
+        Property::Char(x) => Some(*x as f64),
+        Property::UChar(x) => Some(*x as f64),
+        Property::Short(x) => Some(*x as f64),
+        Property::UShort(x) => Some(*x as f64),
+        Property::Int(x) => Some(*x as f64),
+        Property::UInt(x) => Some(*x as f64),
+        Property::Float(x) => Some(*x as f64),
+        Property::Double(x) => Some(*x),
+        _ => None,
+    }
+}
+
+/// Returns the color channel normalized in `[0.0, 1.0]`.
+fn channel(property: &Property) -> Option<f64> {
+    match property {
+        Property::UChar(x) => Some(*x as f64 / u8::MAX as f64),
+        Property::UShort(x) => Some(*x as f64 / u16::MAX as f64),
+        Property::Float(x) => Some(*x as f64),
+        Property::Double(x) => Some(*x),
+        _ => None,
+    }
+}
+
+/// Returns the list property as indices.
+fn indices(property: &Property) -> Option<Vec<usize>> {
+    fn cast<T: TryInto<usize> + Copy>(v: &[T]) -> Option<Vec<usize>> {
+        v.iter().map(|&x| x.try_into().ok()).collect()
+    }
+
+    match property {
+        Property::ListChar(v) => cast(v),
+        Property::ListUChar(v) => cast(v),
+        Property::ListShort(v) => cast(v),
+        Property::ListUShort(v) => cast(v),
+        Property::ListInt(v) => cast(v),
+        Property::ListUInt(v) => cast(v),
+        _ => None,
+    }
+}
+
+fn vec3(element: &DefaultElement, keys: [&str; 3]) -> Option<Vec3> {
+    let x = scalar(element.get(keys[0])?)?;
+    let y = scalar(element.get(keys[1])?)?;
+    let z = scalar(element.get(keys[2])?)?;
+    Some(Vec3::new(x, y, z))
+}
+
+fn color(element: &DefaultElement, keys: [&str; 3]) -> Option<Color> {
+    let r = channel(element.get(keys[0])?)?;
+    let g = channel(element.get(keys[1])?)?;
+    let b = channel(element.get(keys[2])?)?;
+    Some(Color::new(r, g, b))
+}
+
+fn uv(element: &DefaultElement, keys: [&str; 2]) -> Option<(f64, f64)> {
+    let u = scalar(element.get(keys[0])?)?;
+    let v = scalar(element.get(keys[1])?)?;
+    Some((u, v))
+}
@@ -0,0 +1,750 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::filter::FilterType;
+use crate::geometry::Vec3;
+use crate::io::{load_ply, load_stl, Error, Result};
+use crate::light::{DirectionalLight, EnvironmentMap, Light, LightSamplerType, PointLight};
+use crate::light::{Sky, SpotLight};
+use crate::material::{CheckerTexture, ColorTexture, DiffuseLight, Dilectric, ImageTexture};
+use crate::material::{Lambertian, Material, Metal, MetallicRoughness, Texture};
+use crate::render::{
+    AdaptiveSampling, Aov, Denoiser, IntegratorType, PhotonSettings, Scene, TileOrder,
+    DEFAULT_MAX_DEPTH, DEFAULT_TILE_SIZE,
+};
+use crate::sampler::SamplerType;
+use crate::shape::{Shape, Sphere, Triangle, TriangleMesh};
+use crate::utils::hash_bytes;
+
+/// The format of scene description.
+///
+/// # Variants
+/// * `Json`    - JSON format.
+/// * `Toml`    - TOML format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    Json,
+    Toml,
+}
+
+impl SceneFormat {
+    /// Returns the format guessed from the file extension.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        match path.as_ref().extension()?.to_str()? {
+            "json" => Some(SceneFormat::Json),
+            "toml" => Some(SceneFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// A struct to represent render settings described in scene file.
+///
+/// # Arguments
+/// * `width`       - The image width.
+/// * `height`      - The image height.
+/// * `samples`     - The number of samples per pixel.
+/// * `max_depth`   - The maximum number of ray bounces.
+/// * `roulette_depth` - The number of bounces after which paths are terminated by Russian roulette, or `None` to disable it.
+/// * `clamp`       - The maximum luminance of each sample, or `None` not to clamp.
+/// * `seed`        - The seed of random generators.
+/// * `sampler`     - The type of sampler.
+/// * `filter`      - The type of reconstruction filter.
+/// * `filter_radius` - The radius of the filter, or the default radius of the type if `None`.
+/// * `adaptive`    - The optional parameters of adaptive sampling, which replaces `samples`.
+/// * `tile_size`   - The width and height of tiles.
+/// * `tile_order`  - The order to render tiles.
+/// * `aovs`        - The AOVs to output along with the image.
+/// * `denoise`     - The optional parameters of the denoiser applied after rendering.
+/// * `light_sampler` - The strategy to select emissive primitives to sample.
+/// * `integrator`  - The integrator to estimate the color of camera rays, which is passed to `RenderOptions`.
+/// * `ao_radius`   - The radius of the ambient occlusion integrator, or `1.0` if `None`.
+/// * `photons`     - The parameters of photon mapping.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    pub samples: usize,
+    pub max_depth: u32,
+    pub roulette_depth: Option<u32>,
+    pub clamp: Option<f64>,
+    pub seed: u64,
+    pub sampler: SamplerType,
+    pub filter: FilterType,
+    pub filter_radius: Option<f64>,
+    pub adaptive: Option<AdaptiveSampling>,
+    pub tile_size: u32,
+    pub tile_order: TileOrder,
+    pub aovs: Vec<Aov>,
+    pub denoise: Option<Denoiser>,
+    pub light_sampler: LightSamplerType,
+    pub integrator: IntegratorType,
+    pub ao_radius: Option<f64>,
+    pub photons: PhotonSettings,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            width: 400,
+            height: 200,
+            samples: 10,
+            max_depth: DEFAULT_MAX_DEPTH,
+            roulette_depth: None,
+            clamp: None,
+            seed: 0,
+            sampler: SamplerType::default(),
+            filter: FilterType::default(),
+            filter_radius: None,
+            adaptive: None,
+            tile_size: DEFAULT_TILE_SIZE,
+            tile_order: TileOrder::default(),
+            aovs: Vec::new(),
+            denoise: None,
+            light_sampler: LightSamplerType::default(),
+            integrator: IntegratorType::default(),
+            ao_radius: None,
+            photons: PhotonSettings::default(),
+        }
+    }
+}
+
+/// A scene loaded from scene file.
+///
+/// # Arguments
+/// * `scene`       - The scene to render.
+/// * `settings`    - The render settings.
+/// * `hash`        - The hash of the scene description, which does not cover the files referenced from it.
+pub struct SceneFile {
+    pub scene: Scene,
+    pub settings: RenderSettings,
+    pub hash: u64,
+}
+
+/// Reads scene description.
+///
+/// Errors are reported with the path to the invalid value such as `materials.glass.ir`.
+///
+/// # Arguments
+/// * `content` - The scene description.
+/// * `format`  - The format of `content`.
+/// * `base`    - The directory to resolve relative paths of meshes and images.
+///
+/// # Examples
+/// ```
+/// use raytrs::io::{read_scene, SceneFormat};
+///
+/// let toml = r#"
+/// [camera]
+/// look_from = [13.0, 2.0, 3.0]
+/// look_at = [0.0, 0.0, 0.0]
+/// vfov = 20.0
+///
+/// [render]
+/// width = 300
+/// height = 200
+/// samples = 4
+///
+/// [textures.checker]
+/// type = "checker"
+/// odd = [0.2, 0.3, 0.1]
+/// even = [0.9, 0.9, 0.9]
+/// freq = 10.0
+///
+/// [materials.ground]
+/// type = "lambertian"
+/// albedo = "checker"
+///
+/// [materials.glass]
+/// type = "dilectric"
+/// ir = 1.5
+///
+/// [[shapes]]
+/// type = "sphere"
+/// center = [0.0, -1000.0, 0.0]
+/// radius = 1000.0
+/// material = "ground"
+///
+/// [[shapes]]
+/// type = "sphere"
+/// center = [0.0, 1.0, 0.0]
+/// radius = 1.0
+/// material = "glass"
+///
+/// [[lights]]
+/// type = "spot"
+/// position = [0.0, 5.0, 0.0]
+/// direction = [0.0, -1.0, 0.0]
+/// intensity = [20.0, 20.0, 20.0]
+/// cone_angle = 30.0
+/// falloff_angle = 20.0
+///
+/// [sky]
+/// elevation = 30.0
+/// turbidity = 3.0
+/// "#;
+/// let file = read_scene(toml, SceneFormat::Toml, None).unwrap();
+/// assert_eq!(file.settings.samples, 4);
+///
+/// let err = read_scene(
+///     &toml.replace("ir = 1.5", "ir = \"glass\""),
+///     SceneFormat::Toml,
+///     None,
+/// )
+/// .err()
+/// .unwrap();
+/// assert!(err.to_string().starts_with("materials.glass.ir:"));
+/// ```
+pub fn read_scene(content: &str, format: SceneFormat, base: Option<&Path>) -> Result<SceneFile> {
+    let root: Value = match format {
+        SceneFormat::Json => {
+            serde_json::from_str(content).map_err(|e| Error::Format(e.to_string()))?
+        }
+        SceneFormat::Toml => toml::from_str(content).map_err(|e| Error::Format(e.to_string()))?,
+    };
+    let description: SceneDescription = decode(root, "")?;
+    let (scene, settings) = Builder::new(description, base.unwrap_or(Path::new(""))).build()?;
+    Ok(SceneFile {
+        scene,
+        settings,
+        hash: hash_bytes(content.as_bytes()),
+    })
+}
+
+/// Loads scene file of JSON (`.json`) or TOML (`.toml`).
+///
+/// Relative paths in the file are resolved from the directory of the file.
+///
+/// # Arguments
+/// * `path`    - The path of scene file.
+pub fn load_scene<P>(path: P) -> Result<SceneFile>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let format = SceneFormat::from_path(path).ok_or_else(|| {
+        Error::Format(format!(
+            "unknown scene format of `{}`, expected `.json` or `.toml`",
+            path.display()
+        ))
+    })?;
+    let content = fs::read_to_string(path)?;
+    read_scene(&content, format, path.parent())
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneDescription {
+    camera: CameraDescription,
+    #[serde(default)]
+    render: RenderSettings,
+    #[serde(default)]
+    textures: BTreeMap<String, Value>,
+    #[serde(default)]
+    materials: BTreeMap<String, Value>,
+    #[serde(default)]
+    shapes: Vec<Value>,
+    #[serde(default)]
+    lights: Vec<Value>,
+    sky: Option<SkyDescription>,
+    environment: Option<EnvironmentDescription>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDescription {
+    look_from: [f64; 3],
+    look_at: [f64; 3],
+    #[serde(default = "default_up")]
+    up: [f64; 3],
+    #[serde(default = "default_vfov")]
+    vfov: f64,
+}
+
+fn default_up() -> [f64; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_vfov() -> f64 {
+    90.0
+}
+
+/// A reference to a named texture, or an inline color.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextureRef {
+    Name(String),
+    Color([f64; 3]),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorTextureDescription {
+    color: [f64; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CheckerTextureDescription {
+    odd: TextureRef,
+    even: TextureRef,
+    freq: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ImageTextureDescription {
+    path: PathBuf,
+    #[serde(default)]
+    repeat: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LambertianDescription {
+    albedo: TextureRef,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetalDescription {
+    albedo: TextureRef,
+    #[serde(default)]
+    fuzz: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DilectricDescription {
+    ir: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DiffuseLightDescription {
+    emit: TextureRef,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MetallicRoughnessDescription {
+    base_color: TextureRef,
+    #[serde(default)]
+    metallic: f64,
+    #[serde(default = "default_roughness")]
+    roughness: f64,
+    emissive: Option<TextureRef>,
+}
+
+fn default_roughness() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SphereDescription {
+    center: [f64; 3],
+    radius: f64,
+    material: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TriangleDescription {
+    vertices: [[f64; 3]; 3],
+    material: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PointLightDescription {
+    position: [f64; 3],
+    intensity: [f64; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpotLightDescription {
+    position: [f64; 3],
+    direction: [f64; 3],
+    intensity: [f64; 3],
+    cone_angle: f64,
+    falloff_angle: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DirectionalLightDescription {
+    direction: [f64; 3],
+    irradiance: [f64; 3],
+    #[serde(default)]
+    angular_diameter: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SkyDescription {
+    elevation: f64,
+    #[serde(default)]
+    azimuth: f64,
+    #[serde(default = "default_turbidity")]
+    turbidity: f64,
+    #[serde(default = "default_ground_albedo")]
+    ground_albedo: [f64; 3],
+    #[serde(default = "default_intensity")]
+    intensity: f64,
+}
+
+fn default_turbidity() -> f64 {
+    3.0
+}
+
+fn default_ground_albedo() -> [f64; 3] {
+    [0.3, 0.3, 0.3]
+}
+
+fn default_intensity() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EnvironmentDescription {
+    path: PathBuf,
+    #[serde(default = "default_intensity")]
+    intensity: f64,
+    #[serde(default)]
+    rotation: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MeshDescription {
+    path: PathBuf,
+    material: Option<String>,
+    #[serde(default)]
+    vertex_colors: bool,
+}
+
+/// A builder to resolve references between textures, materials and shapes.
+struct Builder<'a> {
+    description: SceneDescription,
+    base: &'a Path,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    resolving: HashSet<String>,
+    materials: HashMap<String, Arc<dyn Material>>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(description: SceneDescription, base: &'a Path) -> Self {
+        Builder {
+            description,
+            base,
+            textures: HashMap::new(),
+            resolving: HashSet::new(),
+            materials: HashMap::new(),
+        }
+    }
+
+    fn build(mut self) -> Result<(Scene, RenderSettings)> {
+        let settings = self.description.render.clone();
+        let c = &self.description.camera;
+        let camera = Camera::from_lookat(
+            vec3(c.look_from),
+            vec3(c.look_at),
+            vec3(c.up).as_unit(),
+            c.vfov,
+            settings.width,
+            settings.height,
+        );
+
+        let names = self
+            .description
+            .textures
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in names {
+            self.texture(&name, &format!("textures.{}", name))?;
+        }
+        let materials = std::mem::take(&mut self.description.materials);
+        for (name, value) in materials {
+            let material = self.material(value, &format!("materials.{}", name))?;
+            self.materials.insert(name, material);
+        }
+
+        let mut scene = Scene::new(camera);
+        scene.set_max_depth(settings.max_depth);
+        scene.set_russian_roulette(settings.roulette_depth);
+        scene.set_clamp(settings.clamp);
+        scene.set_seed(settings.seed);
+        scene.set_sampler(settings.sampler);
+        scene.set_filter(settings.filter.build(settings.filter_radius));
+        scene.set_tiles(settings.tile_size, settings.tile_order);
+        scene.set_light_sampler(settings.light_sampler);
+        scene.set_photon_settings(settings.photons);
+        let shapes = std::mem::take(&mut self.description.shapes);
+        for (i, value) in shapes.into_iter().enumerate() {
+            scene.push(self.shape(value, &format!("shapes[{}]", i))?);
+        }
+        let lights = std::mem::take(&mut self.description.lights);
+        for (i, value) in lights.into_iter().enumerate() {
+            scene.add_light(light(value, &format!("lights[{}]", i))?);
+        }
+        if let Some(d) = &self.description.sky {
+            scene.set_sky(Sky::new(
+                d.elevation,
+                d.azimuth,
+                d.turbidity,
+                color(d.ground_albedo),
+                d.intensity,
+            ));
+        }
+        if let Some(d) = &self.description.environment {
+            if self.description.sky.is_some() {
+                return Err(invalid(
+                    "environment",
+                    "cannot be used with sky".to_string(),
+                ));
+            }
+            let environment =
+                EnvironmentMap::open(self.base.join(&d.path), d.intensity, d.rotation)
+                    .map_err(|e| invalid("environment.path", e.to_string()))?;
+            scene.set_environment(environment);
+        }
+        Ok((scene, settings))
+    }
+
+    /// Returns the named texture, which is built at the first reference.
+    fn texture(&mut self, name: &str, path: &str) -> Result<Arc<dyn Texture>> {
+        if let Some(texture) = self.textures.get(name) {
+            return Ok(Arc::clone(texture));
+        }
+        let value = self
+            .description
+            .textures
+            .get(name)
+            .cloned()
+            .ok_or_else(|| invalid(path, format!("unknown texture `{}`", name)))?;
+        if !self.resolving.insert(name.to_string()) {
+            return Err(invalid(path, format!("circular reference of `{}`", name)));
+        }
+
+        let path = format!("textures.{}", name);
+        let (kind, value) = tagged(value, &path)?;
+        let texture: Arc<dyn Texture> = match kind.as_str() {
+            "color" => {
+                let d: ColorTextureDescription = decode(value, &path)?;
+                Arc::new(ColorTexture::new(color(d.color)))
+            }
+            "checker" => {
+                let d: CheckerTextureDescription = decode(value, &path)?;
+                let odd = self.texture_ref(&d.odd, &format!("{}.odd", path))?;
+                let even = self.texture_ref(&d.even, &format!("{}.even", path))?;
+                Arc::new(CheckerTexture::new(odd, even, d.freq))
+            }
+            "image" => {
+                let d: ImageTextureDescription = decode(value, &path)?;
+                let image = ImageTexture::open(self.base.join(&d.path))
+                    .map_err(|e| invalid(&format!("{}.path", path), e.to_string()))?;
+                if d.repeat {
+                    Arc::new(image.repeat())
+                } else {
+                    Arc::new(image)
+                }
+            }
+            _ => return Err(unknown_type(&path, &kind)),
+        };
+        self.resolving.remove(name);
+        self.textures.insert(name.to_string(), Arc::clone(&texture));
+        Ok(texture)
+    }
+
+    fn texture_ref(&mut self, texture: &TextureRef, path: &str) -> Result<Box<dyn Texture>> {
+        match texture {
+            TextureRef::Name(name) => Ok(Box::new(self.texture(name, path)?)),
+            TextureRef::Color(c) => Ok(Box::new(ColorTexture::new(color(*c)))),
+        }
+    }
+
+    fn material(&mut self, value: Value, path: &str) -> Result<Arc<dyn Material>> {
+        let (kind, value) = tagged(value, path)?;
+        let material: Arc<dyn Material> = match kind.as_str() {
+            "lambertian" => {
+                let d: LambertianDescription = decode(value, path)?;
+                let albedo = self.texture_ref(&d.albedo, &format!("{}.albedo", path))?;
+                Arc::new(Lambertian::new(albedo))
+            }
+            "metal" => {
+                let d: MetalDescription = decode(value, path)?;
+                let albedo = self.texture_ref(&d.albedo, &format!("{}.albedo", path))?;
+                Arc::new(Metal::new(albedo, d.fuzz))
+            }
+            "dilectric" => {
+                let d: DilectricDescription = decode(value, path)?;
+                Arc::new(Dilectric::new(d.ir))
+            }
+            "diffuse_light" => {
+                let d: DiffuseLightDescription = decode(value, path)?;
+                let emit = self.texture_ref(&d.emit, &format!("{}.emit", path))?;
+                Arc::new(DiffuseLight::new(emit))
+            }
+            "metallic_roughness" => {
+                let d: MetallicRoughnessDescription = decode(value, path)?;
+                let base_color =
+                    self.texture_ref(&d.base_color, &format!("{}.base_color", path))?;
+                let mut m = MetallicRoughness::new(base_color, d.metallic, d.roughness);
+                if let Some(emissive) = &d.emissive {
+                    m = m.with_emissive(self.texture_ref(emissive, &format!("{}.emissive", path))?);
+                }
+                Arc::new(m)
+            }
+            _ => return Err(unknown_type(path, &kind)),
+        };
+        Ok(material)
+    }
+
+    fn material_ref(&self, name: &str, path: &str) -> Result<Arc<dyn Material>> {
+        self.materials
+            .get(name)
+            .cloned()
+            .ok_or_else(|| invalid(path, format!("unknown material `{}`", name)))
+    }
+
+    fn shape(&mut self, value: Value, path: &str) -> Result<Box<dyn Shape>> {
+        let (kind, value) = tagged(value, path)?;
+        let shape: Box<dyn Shape> = match kind.as_str() {
+            "sphere" => {
+                let d: SphereDescription = decode(value, path)?;
+                let material = self.material_ref(&d.material, &format!("{}.material", path))?;
+                Box::new(Sphere::new(vec3(d.center), d.radius, material))
+            }
+            "triangle" => {
+                let d: TriangleDescription = decode(value, path)?;
+                let material = self.material_ref(&d.material, &format!("{}.material", path))?;
+                let [v0, v1, v2] = d.vertices.map(vec3);
+                Box::new(Triangle::new(v0, v1, v2, material))
+            }
+            "mesh" => {
+                let d: MeshDescription = decode(value, path)?;
+                let file = self.base.join(&d.path);
+                let mesh = match file.extension().and_then(|e| e.to_str()) {
+                    Some("ply") => load_ply(&file),
+                    Some("stl") => load_stl(&file),
+                    _ => Err(Error::Format("expected `.ply` or `.stl`".to_string())),
+                }
+                .map_err(|e| invalid(&format!("{}.path", path), e.to_string()))?;
+
+                if d.vertex_colors {
+                    Box::new(TriangleMesh::with_vertex_colors(&mesh, |texture| {
+                        Arc::new(Lambertian::new(texture))
+                    }))
+                } else {
+                    let name = d
+                        .material
+                        .ok_or_else(|| invalid(path, "missing field `material`".to_string()))?;
+                    let material = self.material_ref(&name, &format!("{}.material", path))?;
+                    Box::new(TriangleMesh::new(&mesh, material))
+                }
+            }
+            _ => return Err(unknown_type(path, &kind)),
+        };
+        Ok(shape)
+    }
+}
+
+fn light(value: Value, path: &str) -> Result<Box<dyn Light>> {
+    let (kind, value) = tagged(value, path)?;
+    let light: Box<dyn Light> = match kind.as_str() {
+        "point" => {
+            let d: PointLightDescription = decode(value, path)?;
+            Box::new(PointLight::new(vec3(d.position), color(d.intensity)))
+        }
+        "spot" => {
+            let d: SpotLightDescription = decode(value, path)?;
+            Box::new(SpotLight::new(
+                vec3(d.position),
+                vec3(d.direction),
+                color(d.intensity),
+                d.cone_angle,
+                d.falloff_angle.unwrap_or(d.cone_angle),
+            ))
+        }
+        "directional" => {
+            let d: DirectionalLightDescription = decode(value, path)?;
+            Box::new(DirectionalLight::new(
+                vec3(d.direction),
+                color(d.irradiance),
+                d.angular_diameter,
+            ))
+        }
+        _ => return Err(unknown_type(path, &kind)),
+    };
+    Ok(light)
+}
+
+/// Deserializes `value` reporting the path to the invalid value prefixed by `path`.
+fn decode<T>(value: Value, path: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let inner = e.path().to_string();
+        let path = match (path.is_empty(), inner.as_str()) {
+            (_, ".") => path.to_string(),
+            (true, _) => inner,
+            (false, _) if inner.starts_with('[') => format!("{}{}", path, inner),
+            (false, _) => format!("{}.{}", path, inner),
+        };
+        invalid(&path, e.into_inner().to_string())
+    })
+}
+
+/// Splits the object into the value of `type` field and the rest.
+fn tagged(value: Value, path: &str) -> Result<(String, Value)> {
+    let Value::Object(mut object) = value else {
+        return Err(invalid(path, "expected a table".to_string()));
+    };
+    match object.remove("type") {
+        Some(Value::String(kind)) => Ok((kind, Value::Object(object))),
+        Some(_) => Err(invalid(
+            &format!("{}.type", path),
+            "expected a string".to_string(),
+        )),
+        None => Err(invalid(path, "missing field `type`".to_string())),
+    }
+}
+
+fn invalid(path: &str, message: String) -> Error {
+    Error::Scene {
+        path: path.to_string(),
+        message,
+    }
+}
+
+fn unknown_type(path: &str, kind: &str) -> Error {
+    invalid(
+        &format!("{}.type", path),
+        format!("unknown type `{}`", kind),
+    )
+}
+
+fn vec3(v: [f64; 3]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
+
+fn color(c: [f64; 3]) -> Color {
+    Color::new(c[0], c[1], c[2])
+}
@@ -0,0 +1,71 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+use crate::geometry::Vec3;
+use crate::io::{Error, Result};
+use crate::shape::Mesh;
+
+/// Reads ASCII or binary STL from `reader`.
+///
+/// Shared vertices are merged, and facet normals are ignored in favor of the winding order.
+///
+/// # Arguments
+/// * `reader`  - A reader of STL.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+///
+/// use raytrs::io::read_stl;
+///
+/// let mut reader = Cursor::new(
+///     b"solid tri
+///       facet normal 0 0 1
+///         outer loop
+///           vertex 0 0 0
+///           vertex 1 0 0
+///           vertex 0 1 0
+///         endloop
+///       endfacet
+///     endsolid tri"
+///         .to_vec(),
+/// );
+/// let mesh = read_stl(&mut reader).unwrap();
+/// assert_eq!(mesh.positions.len(), 3);
+/// assert_eq!(mesh.faces, vec![[0, 1, 2]]);
+/// ```
+pub fn read_stl<R>(reader: &mut R) -> Result<Mesh>
+where
+    R: Read + Seek,
+{
+    let stl = stl_io::read_stl(reader)?;
+    let mut mesh = Mesh::new();
+    mesh.positions = stl
+        .vertices
+        .iter()
+        .map(|v| Vec3::new(v[0] as f64, v[1] as f64, v[2] as f64))
+        .collect();
+    mesh.faces = stl.faces.iter().map(|f| f.vertices).collect();
+    if mesh
+        .faces
+        .iter()
+        .flatten()
+        .any(|&i| i >= mesh.positions.len())
+    {
+        return Err(Error::Format("vertex index out of range".to_string()));
+    }
+    Ok(mesh)
+}
+
+/// Loads ASCII or binary STL file.
+///
+/// # Arguments
+/// * `path`    - The path of STL file.
+pub fn load_stl<P>(path: P) -> Result<Mesh>
+where
+    P: AsRef<Path>,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+    read_stl(&mut reader)
+}
@@ -1,9 +1,13 @@
 pub mod camera;
 pub mod color;
+pub mod filter;
 pub mod geometry;
 pub mod interval;
+pub mod io;
+pub mod light;
 pub mod material;
 pub mod ray;
 pub mod render;
+pub mod sampler;
 pub mod shape;
 pub mod utils;
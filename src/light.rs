@@ -0,0 +1,76 @@
+pub(crate) mod directional;
+pub(crate) mod distribution;
+pub(crate) mod environment;
+pub(crate) mod light_sampler;
+pub(crate) mod point;
+pub(crate) mod sky;
+pub(crate) mod spot;
+
+use std::fmt::Debug;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+
+use self::directional::DirectionalLight as _DirectionalLight;
+use self::environment::EnvironmentMap as _EnvironmentMap;
+use self::light_sampler::LightSamplerType as _LightSamplerType;
+use self::light_sampler::LightTree as _LightTree;
+use self::light_sampler::PowerLightSampler as _PowerLightSampler;
+use self::light_sampler::UniformLightSampler as _UniformLightSampler;
+use self::point::PointLight as _PointLight;
+use self::sky::Sky as _Sky;
+use self::spot::SpotLight as _SpotLight;
+
+pub type DirectionalLight = _DirectionalLight;
+pub type EnvironmentMap = _EnvironmentMap;
+pub type LightSamplerType = _LightSamplerType;
+pub type LightTree = _LightTree;
+pub type PowerLightSampler = _PowerLightSampler;
+pub type UniformLightSampler = _UniformLightSampler;
+pub type PointLight = _PointLight;
+pub type Sky = _Sky;
+pub type SpotLight = _SpotLight;
+
+/// A container to store the light arriving at a point.
+///
+/// # Arguments
+/// * `wi`          - The unit direction toward the light.
+/// * `li`          - The incident light divided by the PDF of `wi`.
+/// * `distance`    - The distance to the light, which is infinite for directional lights.
+#[derive(Debug, Clone, Copy)]
+pub struct LightSample {
+    pub wi: Vec3,
+    pub li: Color,
+    pub distance: f64,
+}
+
+/// A trait for lights which are not geometry, so they cannot be hit by rays.
+///
+/// Lights are sampled at each hit, and the shadow ray toward the light tests the visibility.
+pub trait Light: Sync + Send + Debug {
+    /// Returns `LightSample` arriving at `p`, or `None` if the light does not reach `p`.
+    ///
+    /// # Arguments
+    /// * `p`   - The point to be lit.
+    /// * `u`   - Uniform numbers in `[0, 1)` to sample the light.
+    fn sample(&self, p: Vec3, u: (f64, f64)) -> Option<LightSample>;
+}
+
+/// A trait to select an emitter to sample at each hit.
+///
+/// The selection may depend on the point to be lit, so that near and bright emitters are chosen more often.
+pub trait LightSampler: Sync + Send + Debug {
+    /// Returns the index of the selected emitter and its probability, or `None` if there is no emitter.
+    ///
+    /// # Arguments
+    /// * `p`   - The point to be lit.
+    /// * `u`   - A uniform number in `[0, 1)`.
+    fn sample(&self, p: Vec3, u: f64) -> Option<(usize, f64)>;
+
+    /// Returns the probability of selecting the emitter of `index` by `sample` at `p`.
+    ///
+    /// # Arguments
+    /// * `p`       - The point to be lit.
+    /// * `index`   - The index of the emitter.
+    fn pmf(&self, p: Vec3, index: usize) -> f64;
+}
@@ -0,0 +1,73 @@
+use std::f64::consts::PI;
+
+use crate::color::Color;
+use crate::geometry::vec3::orthonormal_basis;
+use crate::geometry::Vec3;
+use crate::light::{Light, LightSample};
+
+/// A light infinitely far away such as the sun, which arrives from the same direction everywhere.
+///
+/// If the angular diameter is positive, the light arrives from a disk of the angle to make soft shadows.
+///
+/// # Arguments
+/// * `direction`       - The unit direction toward the light.
+/// * `irradiance`      - The irradiance on the surface perpendicular to the light.
+/// * `cos_half_angle`  - The cosine of the half of the angular diameter.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{DirectionalLight, Light};
+///
+/// let sun = DirectionalLight::new(Vec3::new(0.0, 1.0, 0.0), Color::WHITE, 0.0);
+/// let sample = sun.sample(Vec3::zeros(), (0.5, 0.5)).unwrap();
+/// assert_eq!(sample.wi, Vec3::new(0.0, 1.0, 0.0));
+/// assert_eq!(sample.distance, f64::INFINITY);
+///
+/// let soft = DirectionalLight::new(Vec3::new(0.0, 1.0, 0.0), Color::WHITE, 0.53);
+/// let sample = soft.sample(Vec3::zeros(), (0.3, 0.7)).unwrap();
+/// assert!(sample.wi.y() < &1.0 && sample.wi.y() > &0.99);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirectionalLight {
+    pub direction: Vec3,
+    pub irradiance: Color,
+    pub cos_half_angle: f64,
+}
+
+impl DirectionalLight {
+    /// Constructs `DirectionalLight`.
+    ///
+    /// # Arguments
+    /// * `direction`           - The direction toward the light.
+    /// * `irradiance`          - The irradiance on the surface perpendicular to the light.
+    /// * `angular_diameter`    - The angular diameter in degrees, e.g. `0.53` for the sun.
+    pub fn new(direction: Vec3, irradiance: Color, angular_diameter: f64) -> Self {
+        DirectionalLight {
+            direction: direction.as_unit(),
+            irradiance,
+            cos_half_angle: (0.5 * angular_diameter.max(0.0)).to_radians().cos(),
+        }
+    }
+}
+
+impl Light for DirectionalLight {
+    /// Returns the direction uniformly distributed in the cone of the angular diameter.
+    fn sample(&self, _p: Vec3, u: (f64, f64)) -> Option<LightSample> {
+        let wi = if self.cos_half_angle < 1.0 {
+            let z = 1.0 + u.0 * (self.cos_half_angle - 1.0);
+            let r = (1.0 - z * z).max(0.0).sqrt();
+            let phi = 2.0 * PI * u.1;
+            let (s, t) = orthonormal_basis(self.direction);
+            s * (r * phi.cos()) + t * (r * phi.sin()) + self.direction * z
+        } else {
+            self.direction
+        };
+        Some(LightSample {
+            wi,
+            li: self.irradiance,
+            distance: f64::INFINITY,
+        })
+    }
+}
@@ -0,0 +1,130 @@
+/// A piecewise-constant 1D distribution over `[0, 1)` to sample values in proportion to the function.
+///
+/// # Arguments
+/// * `func`        - The non-negative function values of the pieces.
+/// * `cdf`         - The cumulative distribution at the boundaries of the pieces.
+/// * `integral`    - The integral of the function over `[0, 1)`.
+#[derive(Debug, Clone)]
+pub(crate) struct Distribution1D {
+    func: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    /// Constructs `Distribution1D`, which is uniform if the function is zero everywhere.
+    ///
+    /// # Arguments
+    /// * `func`    - The non-negative function values of the pieces.
+    pub(crate) fn new(func: Vec<f64>) -> Self {
+        let n = func.len() as f64;
+        let mut cdf = Vec::with_capacity(func.len() + 1);
+        cdf.push(0.0);
+        for f in &func {
+            cdf.push(cdf[cdf.len() - 1] + f.max(0.0) / n);
+        }
+        let integral = cdf[func.len()];
+        if integral > 0.0 {
+            cdf.iter_mut().for_each(|c| *c /= integral);
+        } else {
+            cdf.iter_mut()
+                .enumerate()
+                .for_each(|(i, c)| *c = i as f64 / n);
+        }
+        Distribution1D {
+            func,
+            cdf,
+            integral,
+        }
+    }
+
+    /// Returns the sampled value in `[0, 1)`, its PDF and the index of the piece.
+    ///
+    /// # Arguments
+    /// * `u`   - A uniform number in `[0, 1)`.
+    pub(crate) fn sample(&self, u: f64) -> (f64, f64, usize) {
+        // The last boundary not greater than `u`, which skips pieces of zero.
+        let i = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .clamp(1, self.func.len())
+            - 1;
+        let width = self.cdf[i + 1] - self.cdf[i];
+        let du = if width > 0.0 {
+            (u - self.cdf[i]) / width
+        } else {
+            0.0
+        };
+        let x = ((i as f64 + du) / self.func.len() as f64).min(1.0 - f64::EPSILON);
+        (x, self.pdf(i), i)
+    }
+
+    /// Returns the PDF of sampling a value in the `i`-th piece.
+    pub(crate) fn pdf(&self, i: usize) -> f64 {
+        if self.integral > 0.0 {
+            self.func[i].max(0.0) / self.integral
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the probability of sampling a value in the `i`-th piece.
+    pub(crate) fn pmf(&self, i: usize) -> f64 {
+        self.pdf(i) / self.func.len() as f64
+    }
+
+    /// Returns the index of the piece which contains `x` in `[0, 1]`.
+    pub(crate) fn index(&self, x: f64) -> usize {
+        ((x * self.func.len() as f64) as usize).min(self.func.len() - 1)
+    }
+
+    pub(crate) fn integral(&self) -> f64 {
+        self.integral
+    }
+}
+
+/// A piecewise-constant 2D distribution over `[0, 1)^2`, sampled by the marginal distribution of rows
+/// and then the conditional distribution in the row.
+#[derive(Debug, Clone)]
+pub(crate) struct Distribution2D {
+    conditionals: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    /// Constructs `Distribution2D`.
+    ///
+    /// # Arguments
+    /// * `func`    - The non-negative function values in row-major order.
+    /// * `width`   - The number of pieces along `u`.
+    /// * `height`  - The number of pieces along `v`.
+    pub(crate) fn new(func: &[f64], width: usize, height: usize) -> Self {
+        let conditionals = func
+            .chunks(width)
+            .take(height)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect::<Vec<_>>();
+        let marginal = Distribution1D::new(conditionals.iter().map(|c| c.integral()).collect());
+        Distribution2D {
+            conditionals,
+            marginal,
+        }
+    }
+
+    /// Returns the sampled point `(u, v)` and its PDF with respect to the area of `[0, 1)^2`.
+    ///
+    /// # Arguments
+    /// * `u`   - Uniform numbers in `[0, 1)`.
+    pub(crate) fn sample(&self, u: (f64, f64)) -> ((f64, f64), f64) {
+        let (v, pdf_v, row) = self.marginal.sample(u.1);
+        let (u, pdf_u, _) = self.conditionals[row].sample(u.0);
+        ((u, v), pdf_u * pdf_v)
+    }
+
+    /// Returns the PDF of sampling the point `(u, v)`.
+    pub(crate) fn pdf(&self, u: f64, v: f64) -> f64 {
+        let row = self.marginal.index(v);
+        let conditional = &self.conditionals[row];
+        conditional.pdf(conditional.index(u)) * self.marginal.pdf(row)
+    }
+}
@@ -0,0 +1,177 @@
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::light::distribution::Distribution2D;
+
+/// An environment map of an equirectangular image surrounding the scene infinitely far away.
+///
+/// The center of the image is toward `-z`, the right is toward `+x` and the top is toward `+y`.
+/// Directions are importance sampled by a piecewise-constant distribution of pixels in proportion to
+/// their luminance times `sin θ`, which is the area of pixels on the sphere,
+/// so that small bright regions such as softboxes and the sun are sampled well.
+///
+/// # Arguments
+/// * `pixels`          - The radiance of pixels in row-major order.
+/// * `width`           - The image width.
+/// * `height`          - The image height.
+/// * `intensity`       - The scale of the radiance.
+/// * `rotation`        - The rotation around `+y` in radians.
+/// * `distribution`    - The distribution of pixels to sample.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::EnvironmentMap;
+///
+/// // A black environment with a bright pixel toward `-z`.
+/// let (width, height) = (8, 4);
+/// let mut pixels = vec![Color::BLACK; width * height];
+/// pixels[width + width / 2] = Color::new(10.0, 10.0, 10.0);
+/// let env = EnvironmentMap::from_pixels(pixels, width, height, 1.0, 0.0);
+///
+/// let (wi, radiance, pdf) = env.sample((0.3, 0.6));
+/// assert!(wi.z() < &0.0);
+/// assert_eq!(radiance.r, 10.0);
+/// assert!((pdf - env.pdf(wi)).abs() < 1e-9);
+/// assert_eq!(env.pdf(Vec3::new(0.0, 0.0, 1.0)), 0.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    pixels: Vec<Color>,
+    width: usize,
+    height: usize,
+    pub intensity: f64,
+    pub rotation: f64,
+    distribution: Distribution2D,
+}
+
+impl EnvironmentMap {
+    /// Constructs `EnvironmentMap` from the image file, and returns error if it fails to load.
+    ///
+    /// HDR formats such as Radiance HDR and OpenEXR keep the radiance above `1.0`.
+    ///
+    /// # Arguments
+    /// * `path`        - The path of image file.
+    /// * `intensity`   - The scale of the radiance.
+    /// * `rotation`    - The rotation around `+y` in degrees.
+    pub fn open<P>(path: P, intensity: f64, rotation: f64) -> image::ImageResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let img = image::open(path)?.into_rgb32f();
+        let (w, h) = img.dimensions();
+        let pixels = img
+            .pixels()
+            .map(|p| Color::new(p[0] as f64, p[1] as f64, p[2] as f64))
+            .collect();
+        Ok(Self::from_pixels(
+            pixels, w as usize, h as usize, intensity, rotation,
+        ))
+    }
+
+    /// Constructs `EnvironmentMap` from pixels in row-major order.
+    ///
+    /// # Arguments
+    /// * `pixels`      - The radiance of pixels, the first pixel is the top-left corner.
+    /// * `width`       - The image width.
+    /// * `height`      - The image height.
+    /// * `intensity`   - The scale of the radiance.
+    /// * `rotation`    - The rotation around `+y` in degrees.
+    pub fn from_pixels(
+        pixels: Vec<Color>,
+        width: usize,
+        height: usize,
+        intensity: f64,
+        rotation: f64,
+    ) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "pixels must be width * height"
+        );
+        let func = pixels
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let theta = PI * ((i / width) as f64 + 0.5) / height as f64;
+                c.luminance().max(0.0) * theta.sin()
+            })
+            .collect::<Vec<_>>();
+        Self {
+            distribution: Distribution2D::new(&func, width, height),
+            pixels,
+            width,
+            height,
+            intensity,
+            rotation: rotation.to_radians(),
+        }
+    }
+
+    /// Returns the radiance arriving from the direction `d`.
+    ///
+    /// # Arguments
+    /// * `d`   - The direction toward the environment.
+    pub fn radiance(&self, d: Vec3) -> Color {
+        let (u, v) = self.uv_of(d.as_unit());
+        self.lookup(u, v)
+    }
+
+    /// Returns the unit direction sampled in proportion to the distribution, its radiance and its PDF in solid angle.
+    ///
+    /// # Arguments
+    /// * `u`   - Uniform numbers in `[0, 1)`.
+    pub fn sample(&self, u: (f64, f64)) -> (Vec3, Color, f64) {
+        let ((u, v), pdf) = self.distribution.sample(u);
+        let theta = PI * v;
+        let sin_theta = theta.sin();
+        let pdf = if sin_theta > 0.0 {
+            pdf / (2.0 * PI * PI * sin_theta)
+        } else {
+            0.0
+        };
+        (self.direction_of(u, v), self.lookup(u, v), pdf)
+    }
+
+    /// Returns the PDF in solid angle of sampling the direction `d` by `sample`.
+    ///
+    /// # Arguments
+    /// * `d`   - The direction toward the environment.
+    pub fn pdf(&self, d: Vec3) -> f64 {
+        let d = d.as_unit();
+        let sin_theta = (1.0 - d.y() * d.y()).max(0.0).sqrt();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+        let (u, v) = self.uv_of(d);
+        self.distribution.pdf(u, v) / (2.0 * PI * PI * sin_theta)
+    }
+
+    /// Returns the radiance of the pixel containing `(u, v)`.
+    fn lookup(&self, u: f64, v: f64) -> Color {
+        let x = ((u * self.width as f64) as usize).min(self.width - 1);
+        let y = ((v * self.height as f64) as usize).min(self.height - 1);
+        self.pixels[y * self.width + x] * self.intensity
+    }
+
+    /// Returns the image coordinate in `[0, 1]^2` of the unit direction `d`.
+    fn uv_of(&self, d: Vec3) -> (f64, f64) {
+        let phi = d.x().atan2(-d.z()) - self.rotation;
+        let u = (0.5 + phi / (2.0 * PI)).rem_euclid(1.0);
+        let v = d.y().clamp(-1.0, 1.0).acos() / PI;
+        (u, v)
+    }
+
+    /// Returns the unit direction of the image coordinate `(u, v)`, which is the inverse of `uv_of`.
+    fn direction_of(&self, u: f64, v: f64) -> Vec3 {
+        let phi = 2.0 * PI * (u - 0.5) + self.rotation;
+        let theta = PI * v;
+        Vec3::new(
+            theta.sin() * phi.sin(),
+            theta.cos(),
+            -theta.sin() * phi.cos(),
+        )
+    }
+}
@@ -0,0 +1,340 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::geometry::Vec3;
+use crate::light::distribution::Distribution1D;
+use crate::light::LightSampler;
+use crate::shape::aabb::Aabb;
+use crate::shape::Emitter;
+
+/// A light sampler which selects emitters with the same probability.
+///
+/// # Examples
+/// ```
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{LightSampler, UniformLightSampler};
+///
+/// let sampler = UniformLightSampler::new(4);
+/// assert_eq!(sampler.sample(Vec3::zeros(), 0.6), Some((2, 0.25)));
+/// assert_eq!(UniformLightSampler::new(0).sample(Vec3::zeros(), 0.6), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UniformLightSampler {
+    count: usize,
+}
+
+impl UniformLightSampler {
+    /// Constructs `UniformLightSampler`.
+    ///
+    /// # Arguments
+    /// * `count`   - The number of emitters.
+    pub fn new(count: usize) -> Self {
+        UniformLightSampler { count }
+    }
+}
+
+impl LightSampler for UniformLightSampler {
+    fn sample(&self, _p: Vec3, u: f64) -> Option<(usize, f64)> {
+        if self.count == 0 {
+            return None;
+        }
+        let index = ((u * self.count as f64) as usize).min(self.count - 1);
+        Some((index, 1.0 / self.count as f64))
+    }
+
+    fn pmf(&self, _p: Vec3, _index: usize) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            1.0 / self.count as f64
+        }
+    }
+}
+
+/// A light sampler which selects emitters in proportion to their power regardless of the point.
+///
+/// If all emitters have no power, they are selected uniformly.
+///
+/// # Examples
+/// ```
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{LightSampler, PowerLightSampler};
+///
+/// let sampler = PowerLightSampler::new(&[1.0, 3.0]);
+/// assert_eq!(sampler.sample(Vec3::zeros(), 0.2), Some((0, 0.25)));
+/// assert_eq!(sampler.sample(Vec3::zeros(), 0.3), Some((1, 0.75)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PowerLightSampler {
+    distribution: Option<Distribution1D>,
+}
+
+impl PowerLightSampler {
+    /// Constructs `PowerLightSampler`.
+    ///
+    /// # Arguments
+    /// * `powers`  - The power of each emitter.
+    pub fn new(powers: &[f64]) -> Self {
+        let distribution = (!powers.is_empty()).then(|| Distribution1D::new(powers.to_vec()));
+        PowerLightSampler { distribution }
+    }
+}
+
+impl LightSampler for PowerLightSampler {
+    fn sample(&self, _p: Vec3, u: f64) -> Option<(usize, f64)> {
+        let distribution = self.distribution.as_ref()?;
+        let (_, _, index) = distribution.sample(u);
+        Some((index, self.pmf(Vec3::zeros(), index)))
+    }
+
+    fn pmf(&self, _p: Vec3, index: usize) -> f64 {
+        self.distribution.as_ref().map_or(0.0, |d| d.pmf(index))
+    }
+}
+
+/// A node of `LightTree`.
+///
+/// Interior nodes store the index of their right child, the left child always follows its parent.
+#[derive(Debug, Clone)]
+enum LightNode {
+    Interior {
+        bbox: Aabb,
+        power: f64,
+        right: usize,
+    },
+    Leaf {
+        bbox: Aabb,
+        power: f64,
+        index: usize,
+    },
+}
+
+impl LightNode {
+    /// Returns the importance of the node to light `p`, which is the power divided by the squared distance.
+    ///
+    /// The distance is clamped by the radius of the bounding box, so that the importance does not diverge
+    /// when `p` is near or inside the node.
+    fn importance(&self, p: Vec3) -> f64 {
+        let (bbox, power) = match self {
+            LightNode::Interior { bbox, power, .. } => (bbox, *power),
+            LightNode::Leaf { bbox, power, .. } => (bbox, *power),
+        };
+        let radius2 = 0.25 * (bbox.max - bbox.min).norm_squared();
+        let distance2 = (bbox.centroid() - p).norm_squared().max(radius2);
+        if distance2 > 0.0 {
+            power / distance2
+        } else {
+            power
+        }
+    }
+}
+
+/// A light sampler which traverses a bounding volume hierarchy of emitters to select one,
+/// choosing each child in proportion to its power divided by the squared distance to the point.
+///
+/// Emitters are split at the median of their centroids along the longest axis like `Bvh`,
+/// so that far clusters of lights such as a city at night are rarely selected for a point.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{LightSampler, LightTree};
+/// use raytrs::material::{ColorTexture, DiffuseLight};
+/// use raytrs::shape::{Emitter, Sphere};
+///
+/// let light = Arc::new(DiffuseLight::new(Box::new(ColorTexture::new(Color::WHITE))));
+/// let emitters: Vec<Box<dyn Emitter>> = (0..8)
+///     .map(|i| Box::new(Sphere::new(Vec3::new(10.0 * i as f64, 0.0, 0.0), 1.0, light.clone())) as Box<dyn Emitter>)
+///     .collect();
+/// let tree = LightTree::new(&emitters);
+///
+/// // The nearest emitter is selected more often than the farthest one.
+/// let p = Vec3::new(0.0, 2.0, 0.0);
+/// assert!(tree.pmf(p, 0) > tree.pmf(p, 7));
+/// assert!(((0..8).map(|i| tree.pmf(p, i)).sum::<f64>() - 1.0).abs() < 1e-9);
+///
+/// let (index, pmf) = tree.sample(p, 0.1).unwrap();
+/// assert_eq!(pmf, tree.pmf(p, index));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LightTree {
+    nodes: Vec<LightNode>,
+    // The branches from the root to the leaf of each emitter, where `1` bits take the right child.
+    paths: Vec<(u64, u32)>,
+}
+
+impl LightTree {
+    /// Constructs `LightTree`.
+    ///
+    /// # Arguments
+    /// * `emitters`    - The emissive primitives.
+    pub fn new(emitters: &[Box<dyn Emitter>]) -> Self {
+        let mut items = emitters
+            .iter()
+            .enumerate()
+            .map(|(i, emitter)| {
+                let bbox = emitter.bounding_box();
+                (bbox.centroid(), bbox, emitter.power().max(0.0), i)
+            })
+            .collect::<Vec<_>>();
+        let mut tree = LightTree {
+            nodes: Vec::new(),
+            paths: vec![(0, 0); emitters.len()],
+        };
+        if !items.is_empty() {
+            tree.build(&mut items, 0, 0);
+        }
+        tree
+    }
+
+    /// Builds nodes recursively for `items` reached by the branches `path` of `depth`.
+    fn build(&mut self, items: &mut [(Vec3, Aabb, f64, usize)], path: u64, depth: u32) -> usize {
+        let bbox = items
+            .iter()
+            .fold(Aabb::EMPTY, |acc, (_, bbox, _, _)| acc.union(bbox));
+        let power = items.iter().map(|(_, _, power, _)| power).sum();
+        let index = self.nodes.len();
+        if items.len() == 1 {
+            let (_, _, _, emitter) = items[0];
+            self.nodes.push(LightNode::Leaf {
+                bbox,
+                power,
+                index: emitter,
+            });
+            self.paths[emitter] = (path, depth);
+            return index;
+        }
+
+        let centroids = items
+            .iter()
+            .fold(Aabb::EMPTY, |acc, (c, _, _, _)| acc.grow(*c));
+        let axis = centroids.longest_axis();
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| a.0[axis].total_cmp(&b.0[axis]));
+
+        self.nodes.push(LightNode::Interior {
+            bbox,
+            power,
+            right: 0,
+        });
+        let (left, right) = items.split_at_mut(mid);
+        self.build(left, path, depth + 1);
+        let right_index = self.build(right, path | (1 << depth), depth + 1);
+        if let LightNode::Interior { right, .. } = &mut self.nodes[index] {
+            *right = right_index;
+        }
+        index
+    }
+
+    /// Returns the probability of choosing the right child of the interior node at `index`.
+    fn right_probability(&self, p: Vec3, index: usize, right: usize) -> f64 {
+        let left = self.nodes[index + 1].importance(p);
+        let right = self.nodes[right].importance(p);
+        if left + right > 0.0 {
+            right / (left + right)
+        } else {
+            0.5
+        }
+    }
+}
+
+impl LightSampler for LightTree {
+    fn sample(&self, p: Vec3, mut u: f64) -> Option<(usize, f64)> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+        let (mut node, mut pmf) = (0, 1.0);
+        loop {
+            match self.nodes[node] {
+                LightNode::Leaf { index, .. } => return Some((index, pmf)),
+                LightNode::Interior { right, .. } => {
+                    let pr = self.right_probability(p, node, right);
+                    // The uniform number is rescaled to be reused at the next level.
+                    if u < 1.0 - pr {
+                        u /= 1.0 - pr;
+                        pmf *= 1.0 - pr;
+                        node += 1;
+                    } else {
+                        u = ((u - (1.0 - pr)) / pr).min(1.0 - f64::EPSILON);
+                        pmf *= pr;
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn pmf(&self, p: Vec3, index: usize) -> f64 {
+        let Some(&(path, depth)) = self.paths.get(index) else {
+            return 0.0;
+        };
+        let (mut node, mut pmf) = (0, 1.0);
+        for level in 0..depth {
+            let LightNode::Interior { right, .. } = self.nodes[node] else {
+                break;
+            };
+            let pr = self.right_probability(p, node, right);
+            if path & (1 << level) != 0 {
+                pmf *= pr;
+                node = right;
+            } else {
+                pmf *= 1.0 - pr;
+                node += 1;
+            }
+        }
+        pmf
+    }
+}
+
+/// Represents the strategy to select emitters.
+///
+/// # Variants
+/// * `Uniform` - `UniformLightSampler`.
+/// * `Power`   - `PowerLightSampler`.
+/// * `Tree`    - `LightTree`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LightSamplerType {
+    #[default]
+    Uniform,
+    Power,
+    Tree,
+}
+
+impl LightSamplerType {
+    /// Constructs the light sampler for the emitters.
+    ///
+    /// # Arguments
+    /// * `emitters`    - The emissive primitives.
+    pub fn build(&self, emitters: &[Box<dyn Emitter>]) -> Box<dyn LightSampler> {
+        match self {
+            LightSamplerType::Uniform => Box::new(UniformLightSampler::new(emitters.len())),
+            LightSamplerType::Power => {
+                let powers = emitters.iter().map(|e| e.power()).collect::<Vec<_>>();
+                Box::new(PowerLightSampler::new(&powers))
+            }
+            LightSamplerType::Tree => Box::new(LightTree::new(emitters)),
+        }
+    }
+}
+
+impl FromStr for LightSamplerType {
+    type Err = String;
+
+    /// Parses the light sampler type from `uniform`, `power` or `tree`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(LightSamplerType::Uniform),
+            "power" => Ok(LightSamplerType::Power),
+            "tree" => Ok(LightSamplerType::Tree),
+            _ => Err(format!(
+                "unknown light sampler `{}`, expected one of uniform, power, tree",
+                s
+            )),
+        }
+    }
+}
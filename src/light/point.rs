@@ -0,0 +1,53 @@
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::light::{Light, LightSample};
+
+/// A point light whose light falls off with the inverse square of the distance.
+///
+/// # Arguments
+/// * `position`    - The position.
+/// * `intensity`   - The radiant intensity, which is the light arriving at the distance of `1.0`.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{Light, PointLight};
+///
+/// let light = PointLight::new(Vec3::new(0.0, 2.0, 0.0), Color::new(4.0, 4.0, 4.0));
+/// let sample = light.sample(Vec3::zeros(), (0.5, 0.5)).unwrap();
+/// assert_eq!(sample.wi, Vec3::new(0.0, 1.0, 0.0));
+/// assert_eq!(sample.li.r, 1.0);
+/// assert_eq!(sample.distance, 2.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub intensity: Color,
+}
+
+impl PointLight {
+    /// Constructs `PointLight`.
+    pub fn new(position: Vec3, intensity: Color) -> Self {
+        PointLight {
+            position,
+            intensity,
+        }
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, p: Vec3, _u: (f64, f64)) -> Option<LightSample> {
+        let d = self.position - p;
+        let distance2 = d.norm_squared();
+        if distance2 <= 0.0 {
+            return None;
+        }
+        let distance = distance2.sqrt();
+        Some(LightSample {
+            wi: d / distance,
+            li: self.intensity / distance2,
+            distance,
+        })
+    }
+}
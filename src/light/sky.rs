@@ -0,0 +1,232 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::light::DirectionalLight;
+
+/// The scale from luminance in kcd/m² of the model to radiance of the renderer.
+const LUMINANCE_SCALE: f64 = 0.05;
+
+/// The irradiance of the sun outside the atmosphere relative to the luminance of the sky.
+const SOLAR_IRRADIANCE: f64 = 5.0;
+
+/// The angular diameter of the sun in degrees.
+const SUN_ANGULAR_DIAMETER: f64 = 0.53;
+
+/// The wavelengths in micrometers of red, green and blue to attenuate the sun light.
+const WAVELENGTHS: [f64; 3] = [0.65, 0.57, 0.475];
+
+/// The number of directions along each axis to integrate the irradiance of the sky on the ground.
+const IRRADIANCE_STEPS: usize = 32;
+
+/// An analytic daylight model of Preetham et al. to light outdoor scenes without environment maps.
+///
+/// The sky above the horizon is given by the Perez distribution fitted to the sun position and turbidity,
+/// and the ground below the horizon is a diffuse plane lit by the sky and the sun.
+/// The sun itself is not included, which is lit by `sun` as a light.
+///
+/// # Arguments
+/// * `sun_direction`   - The unit direction toward the sun.
+/// * `turbidity`       - The turbidity of the atmosphere, from `2` of clear sky to `10` of hazy sky.
+/// * `ground_albedo`   - The albedo of the ground.
+/// * `intensity`       - The scale of the sky and the sun.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{Light, Sky};
+///
+/// let sky = Sky::new(30.0, 0.0, 3.0, Color::new(0.3, 0.3, 0.3), 1.0);
+///
+/// // The zenith is bluer than the horizon.
+/// let zenith = sky.radiance(Vec3::new(0.0, 1.0, 0.0));
+/// let horizon = sky.radiance(Vec3::new(1.0, 0.01, 0.0));
+/// assert!(zenith.b / zenith.r > horizon.b / horizon.r);
+///
+/// // The sun is in front of the camera at the default azimuth.
+/// let sample = sky.sun().sample(Vec3::zeros(), (0.5, 0.5)).unwrap();
+/// assert!(sample.wi.z() < &0.0 && sample.wi.y() > &0.49);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sky {
+    pub sun_direction: Vec3,
+    pub turbidity: f64,
+    pub ground_albedo: Color,
+    pub intensity: f64,
+    // The zenith angle of the sun.
+    theta_s: f64,
+    // The zenith luminance and chromaticity.
+    zenith: [f64; 3],
+    // The Perez coefficients of the luminance and chromaticity.
+    perez: [[f64; 5]; 3],
+    // The radiance of the ground.
+    ground: Color,
+}
+
+impl Sky {
+    /// Constructs `Sky`.
+    ///
+    /// # Arguments
+    /// * `elevation`       - The elevation of the sun above the horizon in degrees, which is clamped to `[0, 90]`.
+    /// * `azimuth`         - The azimuth of the sun in degrees, which is `0` toward `-z` and `90` toward `+x`.
+    /// * `turbidity`       - The turbidity of the atmosphere, from `2` of clear sky to `10` of hazy sky.
+    /// * `ground_albedo`   - The albedo of the ground.
+    /// * `intensity`       - The scale of the sky and the sun.
+    pub fn new(
+        elevation: f64,
+        azimuth: f64,
+        turbidity: f64,
+        ground_albedo: Color,
+        intensity: f64,
+    ) -> Self {
+        let elevation = elevation.clamp(0.0, 90.0).to_radians();
+        let azimuth = azimuth.to_radians();
+        let sun_direction = Vec3::new(
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+            -elevation.cos() * azimuth.cos(),
+        );
+        let theta_s = FRAC_PI_2 - elevation;
+        let t = turbidity;
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let luminance = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let chromaticity = |m: [[f64; 4]; 3]| {
+            let ts = [theta_s.powi(3), theta_s.powi(2), theta_s, 1.0];
+            let row = |r: [f64; 4]| r.iter().zip(ts).map(|(a, b)| a * b).sum::<f64>();
+            t * t * row(m[0]) + t * row(m[1]) + row(m[2])
+        };
+        let zenith = [
+            luminance.max(0.0),
+            chromaticity([
+                [0.00166, -0.00375, 0.00209, 0.0],
+                [-0.02903, 0.06377, -0.03202, 0.00394],
+                [0.11693, -0.21196, 0.06052, 0.25886],
+            ]),
+            chromaticity([
+                [0.00275, -0.00610, 0.00317, 0.0],
+                [-0.04214, 0.08970, -0.04153, 0.00516],
+                [0.15346, -0.26756, 0.06670, 0.26688],
+            ]),
+        ];
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let mut sky = Sky {
+            sun_direction,
+            turbidity,
+            ground_albedo,
+            intensity,
+            theta_s,
+            zenith,
+            perez,
+            ground: Color::BLACK,
+        };
+        let irradiance = sky.sky_irradiance() + sky.sun_irradiance() * *sun_direction.y();
+        sky.ground = ground_albedo * irradiance / PI;
+        sky
+    }
+
+    /// Returns the radiance of the sky arriving from the direction `d`, where the sun is not included.
+    ///
+    /// # Arguments
+    /// * `d`   - The direction toward the sky.
+    pub fn radiance(&self, d: Vec3) -> Color {
+        let d = d.as_unit();
+        if *d.y() < 0.0 {
+            return self.ground;
+        }
+        // The Perez function diverges at the horizon, which is avoided by clamping the zenith angle.
+        let cos_theta = d.y().max(1e-3);
+        let gamma = d.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+        let [y, x, z] = [0, 1, 2].map(|i| {
+            self.zenith[i] * perez(self.perez[i], cos_theta, gamma)
+                / perez(self.perez[i], 1.0, self.theta_s)
+        });
+        xyy_to_rgb(x, z, y * LUMINANCE_SCALE * self.intensity)
+    }
+
+    /// Returns the sun as `DirectionalLight`, whose color is attenuated through the atmosphere.
+    pub fn sun(&self) -> DirectionalLight {
+        DirectionalLight::new(
+            self.sun_direction,
+            self.sun_irradiance(),
+            SUN_ANGULAR_DIAMETER,
+        )
+    }
+
+    /// Returns the irradiance of the sun perpendicular to its direction.
+    ///
+    /// The light is attenuated by Rayleigh scattering of molecules and Mie scattering of aerosols
+    /// through the optical mass of air along the direction.
+    fn sun_irradiance(&self) -> Color {
+        let theta = self.theta_s.to_degrees();
+        let mass = 1.0 / (self.theta_s.cos() + 0.15 * (93.885 - theta).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+        let [r, g, b] = WAVELENGTHS.map(|lambda: f64| {
+            let rayleigh = (-0.008735 * lambda.powf(-4.08) * mass).exp();
+            let aerosol = (-beta * lambda.powf(-1.3) * mass).exp();
+            rayleigh * aerosol
+        });
+        Color::new(r, g, b) * (SOLAR_IRRADIANCE * self.intensity)
+    }
+
+    /// Returns the irradiance of the sky on the ground, integrated over the upper hemisphere.
+    fn sky_irradiance(&self) -> Color {
+        let mut sum = Color::BLACK;
+        for i in 0..IRRADIANCE_STEPS {
+            // Directions are uniformly distributed in the projected solid angle.
+            let r2 = (i as f64 + 0.5) / IRRADIANCE_STEPS as f64;
+            let (r, y) = (r2.sqrt(), (1.0 - r2).sqrt());
+            for j in 0..IRRADIANCE_STEPS {
+                let phi = 2.0 * PI * (j as f64 + 0.5) / IRRADIANCE_STEPS as f64;
+                sum += self.radiance(Vec3::new(r * phi.cos(), y, r * phi.sin()));
+            }
+        }
+        sum * (PI / (IRRADIANCE_STEPS * IRRADIANCE_STEPS) as f64)
+    }
+}
+
+/// Returns the Perez distribution at the cosine of the zenith angle `cos_theta` and the angle `gamma` to the sun.
+fn perez(c: [f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    let [a, b, c, d, e] = c;
+    let cos_gamma = gamma.cos();
+    (1.0 + a * (b / cos_theta).exp()) * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+/// Returns the linear sRGB color of the chromaticity `(x, y)` and the luminance `luminance`.
+fn xyy_to_rgb(x: f64, y: f64, luminance: f64) -> Color {
+    if y <= 0.0 {
+        return Color::BLACK;
+    }
+    let cx = x * luminance / y;
+    let cz = (1.0 - x - y) * luminance / y;
+    let cy = luminance;
+    Color::new(
+        (3.2406 * cx - 1.5372 * cy - 0.4986 * cz).max(0.0),
+        (-0.9689 * cx + 1.8758 * cy + 0.0415 * cz).max(0.0),
+        (0.0557 * cx - 0.2040 * cy + 1.0570 * cz).max(0.0),
+    )
+}
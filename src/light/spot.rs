@@ -0,0 +1,96 @@
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::light::{Light, LightSample};
+
+/// A point light which emits within a cone.
+///
+/// The intensity is full within `falloff_angle` from the axis,
+/// and falls off smoothly to zero at `cone_angle`.
+///
+/// # Arguments
+/// * `position`    - The position.
+/// * `direction`   - The unit direction of the axis of the cone.
+/// * `intensity`   - The radiant intensity on the axis.
+/// * `cos_cone`    - The cosine of the half angle of the cone.
+/// * `cos_falloff` - The cosine of the half angle where the falloff starts.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::{Light, SpotLight};
+///
+/// let light = SpotLight::new(
+///     Vec3::new(0.0, 1.0, 0.0),
+///     Vec3::new(0.0, -1.0, 0.0),
+///     Color::WHITE,
+///     30.0,
+///     20.0,
+/// );
+/// assert_eq!(light.sample(Vec3::zeros(), (0.5, 0.5)).unwrap().li.r, 1.0);
+/// assert!(light.sample(Vec3::new(1.0, 0.0, 0.0), (0.5, 0.5)).is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpotLight {
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub intensity: Color,
+    pub cos_cone: f64,
+    pub cos_falloff: f64,
+}
+
+impl SpotLight {
+    /// Constructs `SpotLight`.
+    ///
+    /// # Arguments
+    /// * `position`        - The position.
+    /// * `direction`       - The direction of the axis of the cone.
+    /// * `intensity`       - The radiant intensity on the axis.
+    /// * `cone_angle`      - The half angle of the cone in degrees.
+    /// * `falloff_angle`   - The half angle in degrees where the falloff starts, which is clamped to `cone_angle`.
+    pub fn new(
+        position: Vec3,
+        direction: Vec3,
+        intensity: Color,
+        cone_angle: f64,
+        falloff_angle: f64,
+    ) -> Self {
+        SpotLight {
+            position,
+            direction: direction.as_unit(),
+            intensity,
+            cos_cone: cone_angle.to_radians().cos(),
+            cos_falloff: falloff_angle.min(cone_angle).to_radians().cos(),
+        }
+    }
+
+    /// Returns the scale of the intensity in the direction whose cosine to the axis is `cos`.
+    fn falloff(&self, cos: f64) -> f64 {
+        if cos >= self.cos_falloff {
+            return 1.0;
+        }
+        if cos <= self.cos_cone {
+            return 0.0;
+        }
+        let t = (cos - self.cos_cone) / (self.cos_falloff - self.cos_cone);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, p: Vec3, _u: (f64, f64)) -> Option<LightSample> {
+        let d = self.position - p;
+        let distance2 = d.norm_squared();
+        if distance2 <= 0.0 {
+            return None;
+        }
+        let distance = distance2.sqrt();
+        let wi = d / distance;
+        let falloff = self.falloff(-wi.dot(self.direction));
+        (falloff > 0.0).then(|| LightSample {
+            wi,
+            li: self.intensity * (falloff / distance2),
+            distance,
+        })
+    }
+}
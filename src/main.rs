@@ -0,0 +1,534 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, ValueEnum};
+use image::{ImageFormat, RgbImage};
+
+use raytrs::filter::FilterType;
+use raytrs::io::{load_scene, save_exr};
+use raytrs::light::LightSamplerType;
+use raytrs::render::{
+    AdaptiveSampling, Aov, Budget, Checkpoint, Denoiser, Film, IntegratorType, Progress,
+    ProgressiveRenderer, RenderMode, RenderOptions, TileOrder,
+};
+use raytrs::sampler::SamplerType;
+use raytrs::utils::hash_bytes;
+
+/// Renders a scene file of JSON or TOML.
+#[derive(Parser)]
+#[command(name = "raytrs", version, about)]
+struct Args {
+    /// The scene file (`.json` or `.toml`).
+    scene: PathBuf,
+
+    /// The image width, overriding the scene file.
+    #[arg(short = 'W', long)]
+    width: Option<u32>,
+
+    /// The image height, overriding the scene file.
+    #[arg(short = 'H', long)]
+    height: Option<u32>,
+
+    /// The number of samples per pixel, overriding the scene file.
+    #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+    samples: Option<u64>,
+
+    /// Enables adaptive sampling with the relative error threshold, where `--samples` is the maximum.
+    #[arg(long)]
+    adaptive: Option<f64>,
+
+    /// Renders a Whitted-style preview with one sample per pixel, ignoring the samples and the integrator.
+    #[arg(long, conflicts_with = "adaptive")]
+    whitted: bool,
+
+    /// The minimum number of samples per pixel of adaptive sampling.
+    #[arg(long)]
+    min_samples: Option<usize>,
+
+    /// The output path of the heatmap of sample counts.
+    #[arg(long)]
+    heatmap: Option<PathBuf>,
+
+    /// Renders progressively until the number of passes of `--samples` samples per pixel.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    passes: Option<u32>,
+
+    /// Renders progressively until the time budget in seconds, which is unlimited by `--passes` by default.
+    #[arg(long)]
+    time: Option<f64>,
+
+    /// The checkpoint file of progressive rendering, which is resumed if exists.
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// The interval in seconds to save the checkpoint.
+    #[arg(long, default_value_t = 60.0)]
+    checkpoint_interval: f64,
+
+    /// The maximum number of ray bounces, overriding the scene file.
+    #[arg(short = 'd', long)]
+    max_depth: Option<u32>,
+
+    /// The number of bounces after which paths are terminated by Russian roulette, overriding the scene file.
+    #[arg(long)]
+    roulette_depth: Option<u32>,
+
+    /// The maximum luminance of each sample to suppress fireflies, overriding the scene file.
+    #[arg(long)]
+    clamp: Option<f64>,
+
+    /// The seed to make the image reproducible, overriding the scene file.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// The sampler (independent, stratified, halton, sobol or blue_noise), overriding the scene file.
+    #[arg(long)]
+    sampler: Option<SamplerType>,
+
+    /// The reconstruction filter (box, tent, gaussian, mitchell or lanczos), overriding the scene file.
+    #[arg(long)]
+    filter: Option<FilterType>,
+
+    /// The radius of the reconstruction filter in pixels, overriding the scene file.
+    #[arg(long)]
+    filter_radius: Option<f64>,
+
+    /// The strategy to select emissive objects to sample (uniform, power or tree), overriding the scene file.
+    #[arg(long)]
+    light_sampler: Option<LightSamplerType>,
+
+    /// The integrator (path, bidirectional, photon_mapping, progressive_photon_mapping,
+    /// or the debug views normal, albedo, depth, ambient_occlusion, uv and bvh_cost), overriding the scene file.
+    #[arg(long)]
+    integrator: Option<IntegratorType>,
+
+    /// The radius of the ambient occlusion integrator, overriding the scene file.
+    #[arg(long)]
+    ao_radius: Option<f64>,
+
+    /// The number of photons emitted in each pass of photon mapping, overriding the scene file.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    photons: Option<u64>,
+
+    /// The initial radius to gather photons, overriding the scene file.
+    #[arg(long)]
+    photon_radius: Option<f64>,
+
+    /// The width and height of tiles, overriding the scene file.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    tile_size: Option<u32>,
+
+    /// The order of tiles (scanline, spiral or hilbert), overriding the scene file.
+    #[arg(long)]
+    tile_order: Option<TileOrder>,
+
+    /// The comma-separated AOVs (depth, normal, albedo, position, uv, material_id or object_id), overriding the scene file.
+    ///
+    /// AOVs are written as layers of OpenEXR output, otherwise as images named `<output>.<aov>.png`.
+    #[arg(long, value_delimiter = ',')]
+    aov: Option<Vec<Aov>>,
+
+    /// Denoises the image, which is guided by albedo and normal AOVs except in progressive rendering.
+    #[arg(long)]
+    denoise: bool,
+
+    /// The number of threads, all cores are used by default.
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads: usize,
+
+    /// The output image path.
+    #[arg(short, long, default_value = "out.png")]
+    output: PathBuf,
+
+    /// The output image format, guessed from the output path by default.
+    #[arg(short, long, value_enum)]
+    format: Option<Format>,
+}
+
+/// The output image format.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Png,
+    Jpeg,
+    Bmp,
+    Tga,
+    Tiff,
+    Ppm,
+    Exr,
+}
+
+impl From<Format> for ImageFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Png => ImageFormat::Png,
+            Format::Jpeg => ImageFormat::Jpeg,
+            Format::Bmp => ImageFormat::Bmp,
+            Format::Tga => ImageFormat::Tga,
+            Format::Tiff => ImageFormat::Tiff,
+            Format::Ppm => ImageFormat::Pnm,
+            Format::Exr => ImageFormat::OpenExr,
+        }
+    }
+}
+
+/// The default minimum number of samples per pixel of adaptive sampling.
+const DEFAULT_MIN_SAMPLES: usize = 16;
+
+/// The interval to update the output image in progressive rendering.
+const PREVIEW_INTERVAL: Duration = Duration::from_secs(10);
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("error: {}", msg);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let format = match args.format {
+        Some(format) => format.into(),
+        None => ImageFormat::from_path(&args.output).map_err(|_| {
+            format!(
+                "cannot guess image format of `{}`, use `--format`",
+                args.output.display()
+            )
+        })?,
+    };
+
+    let budget = match (args.passes, args.time) {
+        (None, None) => None,
+        (passes, time) => Some(Budget {
+            passes,
+            time: time
+                .map(|t| Duration::try_from_secs_f64(t).map_err(|e| format!("--time: {}", e)))
+                .transpose()?,
+        }),
+    };
+    if budget.is_some() && args.adaptive.is_some() {
+        return Err("--adaptive cannot be used with progressive rendering".to_string());
+    }
+    if budget.is_some() && args.whitted {
+        return Err("--whitted cannot be used with progressive rendering".to_string());
+    }
+    if budget.is_none() && args.checkpoint.is_some() {
+        return Err("--checkpoint requires --passes or --time".to_string());
+    }
+    let checkpoint_interval = Duration::try_from_secs_f64(args.checkpoint_interval)
+        .map_err(|e| format!("--checkpoint-interval: {}", e))?;
+
+    let start = Instant::now();
+    eprintln!("loading {}", args.scene.display());
+    let file = load_scene(&args.scene).map_err(|e| format!("{}: {}", args.scene.display(), e))?;
+    let mut scene = file.scene;
+    let settings = file.settings;
+    let aovs = args.aov.unwrap_or(settings.aovs);
+    if budget.is_some() && !aovs.is_empty() {
+        return Err("AOVs cannot be rendered progressively".to_string());
+    }
+    let denoiser = match (args.denoise, settings.denoise) {
+        (_, Some(denoiser)) => Some(denoiser),
+        (true, None) => Some(Denoiser::default()),
+        (false, None) => None,
+    };
+
+    let aspect = settings.width as f64 / settings.height as f64;
+    let (width, height) = match (args.width, args.height) {
+        (Some(w), Some(h)) => (w, h),
+        (Some(w), None) => (w, ((w as f64 / aspect).round() as u32).max(1)),
+        (None, Some(h)) => (((h as f64 * aspect).round() as u32).max(1), h),
+        (None, None) => (settings.width, settings.height),
+    };
+    if width < 2 || height < 2 {
+        return Err(format!(
+            "resolution must be at least 2x2, got {}x{}",
+            width, height
+        ));
+    }
+    scene.set_resolution(width, height);
+    let max_depth = args.max_depth.unwrap_or(settings.max_depth);
+    scene.set_max_depth(max_depth);
+    let roulette_depth = args.roulette_depth.or(settings.roulette_depth);
+    scene.set_russian_roulette(roulette_depth);
+    let clamp = args.clamp.or(settings.clamp);
+    scene.set_clamp(clamp);
+    scene.set_seed(args.seed.unwrap_or(settings.seed));
+    let sampler = args.sampler.unwrap_or(settings.sampler);
+    scene.set_sampler(sampler);
+    let filter = args.filter.unwrap_or(settings.filter);
+    let filter_radius = match (args.filter, args.filter_radius) {
+        (_, Some(radius)) => Some(radius),
+        (Some(_), None) => None,
+        (None, None) => settings.filter_radius,
+    };
+    scene.set_filter(filter.build(filter_radius));
+    let light_sampler = args.light_sampler.unwrap_or(settings.light_sampler);
+    scene.set_light_sampler(light_sampler);
+    let integrator = args.integrator.unwrap_or(settings.integrator);
+    let ao_radius = args.ao_radius.or(settings.ao_radius);
+    let tracer = integrator.build(ao_radius);
+    let mut photons = settings.photons;
+    if let Some(count) = args.photons {
+        photons.photons = count as usize;
+    }
+    if let Some(radius) = args.photon_radius {
+        photons.radius = radius;
+    }
+    scene.set_photon_settings(photons);
+    scene.set_tiles(
+        args.tile_size.unwrap_or(settings.tile_size),
+        args.tile_order.unwrap_or(settings.tile_order),
+    );
+    let mode = match (
+        args.adaptive,
+        settings
+            .adaptive
+            .filter(|_| budget.is_none() && !args.whitted),
+    ) {
+        _ if args.whitted => RenderMode::WHITTED,
+        (Some(threshold), adaptive) => RenderMode::ADAPTIVE(AdaptiveSampling::new(
+            args.min_samples
+                .or(adaptive.map(|a| a.min_samples))
+                .unwrap_or(DEFAULT_MIN_SAMPLES),
+            args.samples.map_or(settings.samples, |s| s as usize),
+            threshold,
+        )),
+        (None, Some(adaptive)) => RenderMode::ADAPTIVE(AdaptiveSampling::new(
+            args.min_samples.unwrap_or(adaptive.min_samples),
+            args.samples.map_or(adaptive.max_samples, |s| s as usize),
+            adaptive.threshold,
+        )),
+        (None, None) => RenderMode::AA(args.samples.map_or(settings.samples, |s| s as usize)),
+    };
+    let samples = match (&mode, &budget) {
+        (RenderMode::AA(samples), Some(_)) => format!("{} samples per pass", samples),
+        (RenderMode::ADAPTIVE(a), _) => {
+            format!("{}-{} adaptive samples", a.min_samples, a.max_samples)
+        }
+        (RenderMode::AA(samples), None) => format!("{} samples", samples),
+        (RenderMode::BASIC, _) => "1 sample".to_string(),
+        (RenderMode::WHITTED, _) => "1 Whitted sample".to_string(),
+    };
+    eprintln!("loaded in {:.2?}", start.elapsed());
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(args.threads)
+        .build()
+        .map_err(|e| e.to_string())?;
+    eprintln!(
+        "rendering {}x{}, {} ({:?}), {:?} filter, {:?} integrator, max depth {}, {} threads",
+        width,
+        height,
+        samples,
+        sampler,
+        filter,
+        integrator,
+        max_depth,
+        pool.current_num_threads()
+    );
+    let start = Instant::now();
+    let film = match (budget, mode) {
+        (Some(budget), RenderMode::AA(samples)) => {
+            // The settings which are not checked by the renderer to resume.
+            let scene_hash = hash_bytes(
+                format!(
+                    "{:016x} {} {:?} {:?} {:?} {:?} {} {:?} {:?} {:?} {:?}",
+                    file.hash,
+                    max_depth,
+                    roulette_depth,
+                    clamp,
+                    filter,
+                    filter_radius,
+                    samples,
+                    light_sampler,
+                    integrator,
+                    ao_radius,
+                    photons
+                )
+                .as_bytes(),
+            );
+            let mut renderer = match &args.checkpoint {
+                Some(path) if path.exists() => {
+                    let checkpoint =
+                        Checkpoint::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                    let renderer = ProgressiveRenderer::resume(&scene, checkpoint, scene_hash)
+                        .map_err(|e| format!("{}: {}", path.display(), e))?
+                        .with_integrator(tracer.as_ref());
+                    eprintln!(
+                        "resumed {} passes from {}",
+                        renderer.passes(),
+                        path.display()
+                    );
+                    renderer
+                }
+                _ => scene.progressive(samples).with_integrator(tracer.as_ref()),
+            };
+            let progressive = Progressive {
+                budget,
+                output: &args.output,
+                format,
+                checkpoint: args.checkpoint.as_deref(),
+                checkpoint_interval,
+                scene_hash,
+            };
+            pool.install(|| progressive.run(&mut renderer))?;
+            renderer.film().clone()
+        }
+        (_, mode) => {
+            let mut options = RenderOptions::new(mode)
+                .with_integrator(tracer.as_ref())
+                .with_progress(print_progress);
+            if !aovs.is_empty() || denoiser.is_some() {
+                options = options.with_aovs();
+            }
+            pool.install(|| scene.render_film(&options))
+        }
+    };
+    eprintln!();
+    eprintln!(
+        "rendered in {:.2?}, {:.1} samples per pixel on average",
+        start.elapsed(),
+        film.total_samples() as f64 / (width as f64 * height as f64)
+    );
+    let stats = film.stats();
+    if let Some(min_depth) = roulette_depth {
+        eprintln!(
+            "Russian roulette after {} bounces terminated {} paths",
+            min_depth, stats.terminated
+        );
+    }
+    if let Some(max) = clamp {
+        eprintln!("clamped {} samples to luminance {}", stats.clamped, max);
+    }
+
+    let film = match denoiser {
+        Some(denoiser) => {
+            let start = Instant::now();
+            let film = denoiser.denoise(&film);
+            eprintln!("denoised in {:.2?}", start.elapsed());
+            film
+        }
+        None => film,
+    };
+    save_film(&film, &args.output, format, &aovs)?;
+    if let Some(path) = &args.heatmap {
+        let format = ImageFormat::from_path(path).unwrap_or(ImageFormat::Png);
+        save(&film.sample_heatmap(), path, format)?;
+    }
+    Ok(())
+}
+
+/// Saves the film, where AOVs are written as layers of OpenEXR or as PNG images next to `path`.
+fn save_film(film: &Film, path: &Path, format: ImageFormat, aovs: &[Aov]) -> Result<(), String> {
+    write_film(film, path, format, aovs)?;
+    eprintln!("saved {}", path.display());
+    if let (Some(buffer), true) = (film.aovs(), format != ImageFormat::OpenExr) {
+        for &aov in aovs {
+            let mut name = path.file_stem().unwrap_or_default().to_os_string();
+            name.push(format!(".{}.png", aov.name()));
+            save(
+                &buffer.to_image(aov),
+                &path.with_file_name(name),
+                ImageFormat::Png,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the film, where OpenEXR keeps the colors in floats and includes AOVs.
+fn write_film(film: &Film, path: &Path, format: ImageFormat, aovs: &[Aov]) -> Result<(), String> {
+    match format {
+        ImageFormat::OpenExr => save_exr(path, film, aovs).map_err(|e| e.to_string()),
+        format => film
+            .to_image()
+            .save_with_format(path, format)
+            .map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn save(img: &RgbImage, path: &Path, format: ImageFormat) -> Result<(), String> {
+    img.save_with_format(path, format)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    eprintln!("saved {}", path.display());
+    Ok(())
+}
+
+fn print_progress(progress: Progress) {
+    let eta = progress.eta().unwrap_or_default();
+    eprint!(
+        "\r{:>3.0}% ({}/{} tiles), ETA {:.1}s ",
+        progress.ratio() * 100.0,
+        progress.completed,
+        progress.total,
+        eta.as_secs_f64()
+    );
+}
+
+/// Settings of progressive rendering to update the output image and the checkpoint periodically.
+struct Progressive<'a> {
+    budget: Budget,
+    output: &'a Path,
+    format: ImageFormat,
+    checkpoint: Option<&'a Path>,
+    checkpoint_interval: Duration,
+    scene_hash: u64,
+}
+
+impl Progressive<'_> {
+    /// Renders passes until the budget, which includes the passes and the time of the checkpoint.
+    fn run(&self, renderer: &mut ProgressiveRenderer) -> Result<(), String> {
+        let remaining = Budget {
+            passes: self
+                .budget
+                .passes
+                .map(|n| n.saturating_sub(renderer.passes())),
+            time: self
+                .budget
+                .time
+                .map(|t| t.saturating_sub(renderer.elapsed())),
+        };
+        let (mut previewed, mut checkpointed) = (Instant::now(), Instant::now());
+        let mut result = Ok(());
+        renderer.run(&remaining, None, |r| {
+            print_pass(r, &self.budget);
+            if result.is_ok() && previewed.elapsed() >= PREVIEW_INTERVAL {
+                result = write_film(r.film(), self.output, self.format, &[]);
+                previewed = Instant::now();
+            }
+            if result.is_ok() && checkpointed.elapsed() >= self.checkpoint_interval {
+                result = self.save_checkpoint(r);
+                checkpointed = Instant::now();
+            }
+        });
+        result?;
+        self.save_checkpoint(renderer)
+    }
+
+    fn save_checkpoint(&self, renderer: &ProgressiveRenderer) -> Result<(), String> {
+        match self.checkpoint {
+            Some(path) => renderer
+                .checkpoint(self.scene_hash)
+                .save(path)
+                .map_err(|e| format!("{}: {}", path.display(), e)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn print_pass(renderer: &ProgressiveRenderer, budget: &Budget) {
+    let passes = match budget.passes {
+        Some(total) => format!("{}/{}", renderer.passes(), total),
+        None => renderer.passes().to_string(),
+    };
+    eprint!(
+        "\rpass {} ({} samples per pixel), {:.1}s ",
+        passes,
+        renderer.passes() as usize * renderer.samples_per_pass(),
+        renderer.elapsed().as_secs_f64()
+    );
+}
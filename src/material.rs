@@ -1,34 +1,120 @@
+pub(crate) mod bsdf;
+pub(crate) mod diffuse_light;
 pub(crate) mod dilectric;
 pub(crate) mod lambertian;
 pub(crate) mod metal;
-pub(crate) mod scatter;
+pub(crate) mod metallic_roughness;
 pub(crate) mod texture;
 
 use std::fmt::Debug;
 
 use crate::color::Color;
 use crate::geometry::Vec3;
-use crate::ray::Ray;
 use crate::shape::HitInfo;
 
+use self::bsdf::BsdfSample as _BsdfSample;
+use self::bsdf::Lobe as _Lobe;
+use self::diffuse_light::DiffuseLight as _DiffuseLight;
 use self::dilectric::Dilectric as _Dilectric;
 use self::lambertian::Lambertian as _Lambertian;
 use self::metal::Metal as _Metal;
-use self::scatter::ScatterInfo;
+use self::metallic_roughness::MetallicRoughness as _MetallicRoughness;
 use self::texture::CheckerTexture as _CheckerTexture;
 use self::texture::ColorTexture as _ColorTexture;
 use self::texture::ImageTexture as _ImageTexture;
+use self::texture::ScaleTexture as _ScaleTexture;
+use self::texture::VertexColorTexture as _VertexColorTexture;
 
+pub type BsdfSample = _BsdfSample;
+pub type DiffuseLight = _DiffuseLight;
 pub type Dilectric = _Dilectric;
+pub type Lobe = _Lobe;
 pub type Lambertian = _Lambertian;
 pub type Metal = _Metal;
+pub type MetallicRoughness = _MetallicRoughness;
 pub type CheckerTexture = _CheckerTexture;
 pub type ColorTexture = _ColorTexture;
 pub type ImageTexture = _ImageTexture;
+pub type ScaleTexture = _ScaleTexture;
+pub type VertexColorTexture = _VertexColorTexture;
 
 /// A trait for object's material.
 pub trait Material: Sync + Send + Debug {
-    fn scatter(&self, ray: &Ray, info: &HitInfo) -> Option<ScatterInfo>;
+    /// Returns `BsdfSample` of the direction scattered toward, or `None` if the ray is absorbed.
+    ///
+    /// # Arguments
+    /// * `wo`      - The unit direction toward the viewer.
+    /// * `info`    - The hit information.
+    /// * `u`       - Uniform numbers in `[0, 1)`, where `u[0]` chooses the lobe and the others sample the direction.
+    fn sample(&self, wo: Vec3, info: &HitInfo, u: [f64; 3]) -> Option<BsdfSample>;
+
+    /// Returns the color emitted by the material, which is black by default.
+    ///
+    /// # Arguments
+    /// * `u`   - The texture coordinate.
+    /// * `v`   - The texture coordinate.
+    /// * `p`   - The hit point.
+    fn emitted(&self, _u: f64, _v: f64, _p: Vec3) -> Color {
+        Color::BLACK
+    }
+
+    /// Returns `true`, if the material emits light, which is `false` by default.
+    ///
+    /// Objects with emissive materials are sampled as lights.
+    fn is_emissive(&self) -> bool {
+        false
+    }
+
+    /// Returns the BSDF times the cosine of `wi` to the normal, which is black by default.
+    ///
+    /// Delta lobes are not included, since they cannot be evaluated for arbitrary directions.
+    ///
+    /// # Arguments
+    /// * `wo`      - The unit direction toward the viewer.
+    /// * `wi`      - The unit direction toward the light.
+    /// * `info`    - The hit information.
+    fn eval(&self, _wo: Vec3, _wi: Vec3, _info: &HitInfo) -> Color {
+        Color::BLACK
+    }
+
+    /// Returns the PDF of `sample` sampling `wi` from non-delta lobes, which is `0.0` by default.
+    ///
+    /// # Arguments
+    /// * `wo`      - The unit direction toward the viewer.
+    /// * `wi`      - The unit direction toward the light.
+    /// * `info`    - The hit information.
+    fn pdf(&self, _wo: Vec3, _wi: Vec3, _info: &HitInfo) -> f64 {
+        0.0
+    }
+
+    /// Returns `true`, if all lobes at the hit point are delta lobes, which is `false` by default.
+    ///
+    /// Photons are not stored on such surfaces, since the light they reflect cannot be estimated from photons.
+    ///
+    /// # Arguments
+    /// * `info`    - The hit information.
+    fn is_delta(&self, _info: &HitInfo) -> bool {
+        false
+    }
+
+    /// Returns the albedo at the hit point, which is black by default.
+    ///
+    /// This is the color of the surface independent of lighting, written to the albedo AOV.
+    ///
+    /// # Arguments
+    /// * `info`    - The hit information.
+    fn albedo(&self, _info: &HitInfo) -> Color {
+        Color::BLACK
+    }
+}
+
+/// Returns the normal flipped to the same side as `wo`, which is the direction toward the viewer.
+pub(crate) fn facing_normal(n: Vec3, wo: Vec3) -> Vec3 {
+    if n.dot(wo) < 0.0 {
+        -n
+    } else {
+        n
+    }
 }
 
 /// A trait for material's texture.
@@ -0,0 +1,108 @@
+use std::f64::consts::PI;
+use std::ops::BitOr;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+
+/// Flags to classify lobes of BSDF.
+///
+/// A lobe is either `REFLECTION` or `TRANSMISSION`, combined with one of `DIFFUSE`, `GLOSSY` or `SPECULAR`.
+/// `SPECULAR` lobes are delta distributions, which cannot be evaluated by `Material::eval`.
+///
+/// # Examples
+/// ```
+/// use raytrs::material::Lobe;
+///
+/// let lobe = Lobe::SPECULAR | Lobe::TRANSMISSION;
+/// assert!(lobe.is_delta());
+/// assert!(lobe.contains(Lobe::TRANSMISSION));
+/// assert!(!lobe.contains(Lobe::REFLECTION));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lobe(u8);
+
+impl Lobe {
+    pub const REFLECTION: Lobe = Lobe(1);
+    pub const TRANSMISSION: Lobe = Lobe(1 << 1);
+    pub const DIFFUSE: Lobe = Lobe(1 << 2);
+    pub const GLOSSY: Lobe = Lobe(1 << 3);
+    pub const SPECULAR: Lobe = Lobe(1 << 4);
+
+    /// Returns `true`, if all flags of `other` are set.
+    pub fn contains(self, other: Lobe) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true`, if the lobe is a delta distribution.
+    pub fn is_delta(self) -> bool {
+        self.contains(Lobe::SPECULAR)
+    }
+}
+
+impl BitOr for Lobe {
+    type Output = Lobe;
+
+    fn bitor(self, rhs: Lobe) -> Self::Output {
+        Lobe(self.0 | rhs.0)
+    }
+}
+
+/// A direction sampled from BSDF.
+///
+/// The throughput of the sample is `f / pdf` for any lobe.
+///
+/// # Arguments
+/// * `wi`      - The unit direction toward the light.
+/// * `f`       - The BSDF times the cosine of `wi` to the normal.
+///   For delta lobes, this is the throughput times `pdf`.
+/// * `pdf`     - The PDF in solid angle, or the probability to choose the lobe for delta lobes.
+/// * `lobe`    - The lobe which `wi` is sampled from.
+#[derive(Debug, Clone, Copy)]
+pub struct BsdfSample {
+    pub wi: Vec3,
+    pub f: Color,
+    pub pdf: f64,
+    pub lobe: Lobe,
+}
+
+impl BsdfSample {
+    /// Returns the throughput of the sample, which is `f / pdf`.
+    pub fn weight(&self) -> Color {
+        self.f / self.pdf
+    }
+}
+
+/// Returns the unit vector uniformly distributed on the unit sphere from uniform numbers in `[0, 1)`.
+pub(crate) fn uniform_sphere(u: (f64, f64)) -> Vec3 {
+    let z = 1.0 - 2.0 * u.0;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * PI * u.1;
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Returns the PDF in solid angle of the direction of `r + fuzz * s`,
+/// where `r` is a unit vector and `s` is uniformly distributed on the unit sphere.
+///
+/// The direction `wi` sees the points on the sphere of radius `fuzz` centered at `r`,
+/// whose area density is converted to solid angle at each intersection.
+pub(crate) fn fuzz_pdf(r: Vec3, fuzz: f64, wi: Vec3) -> f64 {
+    let b = wi.dot(r);
+    let disc = b * b - 1.0 + fuzz * fuzz;
+    if fuzz <= 0.0 || disc < 0.0 {
+        return 0.0;
+    }
+    let root = disc.sqrt();
+    [b - root, b + root]
+        .into_iter()
+        .filter(|&t| t > 0.0)
+        .map(|t| {
+            let m = (wi * t - r) / fuzz;
+            let cos = wi.dot(m).abs();
+            if cos > 0.0 {
+                t * t / (cos * 4.0 * PI * fuzz * fuzz)
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
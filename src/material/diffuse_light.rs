@@ -0,0 +1,45 @@
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::material::Material;
+use crate::shape::HitInfo;
+
+use super::bsdf::BsdfSample;
+use super::Texture;
+
+/// A struct to represent diffuse area light, which emits the color and does not scatter rays.
+///
+/// # Examples
+/// ```
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::material::{ColorTexture, DiffuseLight, Material};
+///
+/// let light = DiffuseLight::new(Box::new(ColorTexture::new(Color::new(4.0, 4.0, 4.0))));
+/// assert!(light.is_emissive());
+/// assert_eq!(light.emitted(0.0, 0.0, Vec3::zeros()).r, 4.0);
+/// ```
+#[derive(Debug)]
+pub struct DiffuseLight {
+    pub emit: Box<dyn Texture>,
+}
+
+impl Material for DiffuseLight {
+    fn sample(&self, _wo: Vec3, _info: &HitInfo, _u: [f64; 3]) -> Option<BsdfSample> {
+        None
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Vec3) -> Color {
+        self.emit.value(u, v, p)
+    }
+
+    fn is_emissive(&self) -> bool {
+        true
+    }
+}
+
+impl DiffuseLight {
+    /// Constructs `DiffuseLight`.
+    pub fn new(emit: Box<dyn Texture>) -> Self {
+        DiffuseLight { emit }
+    }
+}
@@ -1,38 +1,50 @@
 use crate::color::Color;
+use crate::geometry::Vec3;
 use crate::material::Material;
-use crate::ray::Ray;
 use crate::shape::HitInfo;
 
-use super::scatter::ScatterInfo;
+use super::bsdf::{BsdfSample, Lobe};
 
-/// A struct to represent dilectric material.
+/// A struct to represent dilectric material, whose lobes are delta distributions.
 #[derive(Debug)]
 pub struct Dilectric {
     pub ir: f64,
 }
 
 impl Material for Dilectric {
-    /// Returns `ScatterInfo`.
-    /// If the ray is refracted returns scatter with refracted vector.
-    /// Otherwise, returns with reflected vector.
+    /// Returns the refracted direction.
+    /// If the ray is totally reflected, returns the reflected direction.
     ///
     /// # Arguments
-    /// * `ray`     - `Ray` instance.
+    /// * `wo`      - The unit direction toward the viewer.
     /// * `info`    - `HitInfo` instance.
-    fn scatter(&self, ray: &Ray, info: &HitInfo) -> Option<ScatterInfo> {
-        let reflected = ray.direction().reflect(info.n);
+    /// * `_u`      - Uniform numbers, which are not used.
+    fn sample(&self, wo: Vec3, info: &HitInfo, _u: [f64; 3]) -> Option<BsdfSample> {
         let (outward_normal, ni_over_nt) = {
-            if ray.direction().dot(info.n) > 0.0 {
+            if wo.dot(info.n) < 0.0 {
                 (-info.n, self.ir)
             } else {
                 (info.n, self.ir.recip())
             }
         };
-        if let Some(refracted) = (-ray.direction).refract(outward_normal, ni_over_nt) {
-            Some(ScatterInfo::new(Ray::new(info.p, refracted), Color::WHITE))
-        } else {
-            Some(ScatterInfo::new(Ray::new(info.p, reflected), Color::WHITE))
-        }
+        let (wi, lobe) = match wo.refract(outward_normal, ni_over_nt) {
+            Some(refracted) => (refracted.as_unit(), Lobe::SPECULAR | Lobe::TRANSMISSION),
+            None => ((-wo).reflect(info.n), Lobe::SPECULAR | Lobe::REFLECTION),
+        };
+        Some(BsdfSample {
+            wi,
+            f: Color::WHITE,
+            pdf: 1.0,
+            lobe,
+        })
+    }
+
+    fn is_delta(&self, _info: &HitInfo) -> bool {
+        true
+    }
+
+    fn albedo(&self, _info: &HitInfo) -> Color {
+        Color::WHITE
     }
 }
 
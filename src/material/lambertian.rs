@@ -1,9 +1,11 @@
+use std::f64::consts::PI;
+
+use crate::color::Color;
 use crate::geometry::Vec3;
-use crate::material::Material;
-use crate::ray::Ray;
+use crate::material::{facing_normal, Material};
 use crate::shape::HitInfo;
 
-use super::scatter::ScatterInfo;
+use super::bsdf::{uniform_sphere, BsdfSample, Lobe};
 use super::Texture;
 
 /// A struct to represent lambertian material.
@@ -13,17 +15,37 @@ pub struct Lambertian {
 }
 
 impl Material for Lambertian {
-    /// Returns `ScatterInfo`.
-    /// If the scatter direction is close to `0.0` the normal vector will be used as scatter direction.
-    fn scatter(&self, _ray: &Ray, info: &HitInfo) -> Option<ScatterInfo> {
-        let mut scatter_dir = info.n + Vec3::rand_unit();
-
-        if scatter_dir.is_close(0.0) {
-            scatter_dir = info.n;
+    /// Returns the cosine-weighted direction on the side of `wo`.
+    ///
+    /// The direction is the normal plus a uniform unit vector.
+    /// If the direction is close to `0.0` the normal vector will be used as the direction.
+    fn sample(&self, wo: Vec3, info: &HitInfo, u: [f64; 3]) -> Option<BsdfSample> {
+        let n = facing_normal(info.n, wo);
+        let mut wi = n + uniform_sphere((u[1], u[2]));
+        if wi.is_close(0.0) {
+            wi = n;
         }
+        let wi = wi.as_unit();
+        let pdf = self.pdf(wo, wi, info);
+        (pdf > 0.0).then(|| BsdfSample {
+            wi,
+            f: self.eval(wo, wi, info),
+            pdf,
+            lobe: Lobe::DIFFUSE | Lobe::REFLECTION,
+        })
+    }
+
+    fn eval(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> Color {
+        self.albedo.value(info.u, info.v, info.p) * self.pdf(wo, wi, info)
+    }
+
+    /// Returns the PDF of the cosine-weighted direction, which is `cos(theta) / pi`.
+    fn pdf(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> f64 {
+        facing_normal(info.n, wo).dot(wi).max(0.0) / PI
+    }
 
-        let albedo = self.albedo.value(info.u, info.v, info.p);
-        Some(ScatterInfo::new(Ray::new(info.p, scatter_dir), albedo))
+    fn albedo(&self, info: &HitInfo) -> Color {
+        self.albedo.value(info.u, info.v, info.p)
     }
 }
 
@@ -1,13 +1,42 @@
+use crate::color::Color;
 use crate::geometry::vec3::reflect;
 use crate::geometry::Vec3;
-use crate::material::Material;
-use crate::ray::Ray;
+use crate::material::{facing_normal, Material};
 use crate::shape::HitInfo;
 
-use super::scatter::ScatterInfo;
+use super::bsdf::{fuzz_pdf, uniform_sphere, BsdfSample, Lobe};
 use super::Texture;
 
 /// A struct to represent metal material.
+///
+/// The reflection is a delta lobe if `fuzz` is zero, otherwise a glossy lobe.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::material::{ColorTexture, Lobe, Material, Metal};
+/// use raytrs::shape::HitInfo;
+///
+/// let mirror = Arc::new(Metal::new(Box::new(ColorTexture::new(Color::WHITE)), 0.0));
+/// let info = HitInfo::new(1.0, Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0), mirror.clone(), 0.0, 0.0);
+/// let wo = Vec3::new(1.0, 1.0, 0.0).as_unit();
+///
+/// let sample = mirror.sample(wo, &info, [0.5, 0.5, 0.5]).unwrap();
+/// assert!(sample.lobe.is_delta());
+/// assert!((sample.wi - Vec3::new(-1.0, 1.0, 0.0).as_unit()).norm() < 1e-12);
+/// // Delta lobes cannot be evaluated.
+/// assert_eq!(mirror.pdf(wo, sample.wi, &info), 0.0);
+/// assert!(mirror.is_delta(&info));
+///
+/// let brushed = Arc::new(Metal::new(Box::new(ColorTexture::new(Color::WHITE)), 0.3));
+/// let sample = brushed.sample(wo, &info, [0.5, 0.5, 0.5]).unwrap();
+/// assert_eq!(sample.lobe, Lobe::GLOSSY | Lobe::REFLECTION);
+/// assert_eq!(brushed.pdf(wo, sample.wi, &info), sample.pdf);
+/// assert!(!brushed.is_delta(&info));
+/// ```
 #[derive(Debug)]
 pub struct Metal {
     pub(crate) albedo: Box<dyn Texture>,
@@ -15,21 +44,50 @@ pub struct Metal {
 }
 
 impl Material for Metal {
-    /// Returns `ScatterInfo`.
+    /// Returns the reflected direction, which is perturbed by a uniform unit vector scaled by `fuzz`.
     ///
-    /// The hit ray will be reflected randomly.
-    /// If the scatter ray became reflected vector,
-    /// which equals to the dot product of vector `v` and normal `n` becomes `> 0.0`, the returns scatter info.
-    /// Otherwise, returns `None`.
-    fn scatter(&self, ray: &Ray, info: &HitInfo) -> Option<ScatterInfo> {
-        let mut reflected = reflect(ray.direction().as_unit(), info.n);
-        reflected += Vec3::rand_unit() * self.fuzz;
-        if reflected.dot(info.n) > 0.0 {
-            let albedo = self.albedo.value(info.u, info.v, info.p);
-            Some(ScatterInfo::new(Ray::new(info.p, reflected), albedo))
-        } else {
-            None
+    /// If the direction goes below the surface, returns `None`.
+    fn sample(&self, wo: Vec3, info: &HitInfo, u: [f64; 3]) -> Option<BsdfSample> {
+        let n = facing_normal(info.n, wo);
+        let reflected = reflect(-wo, n);
+        let albedo = self.albedo.value(info.u, info.v, info.p);
+        if self.fuzz <= 0.0 {
+            return (reflected.dot(n) > 0.0).then_some(BsdfSample {
+                wi: reflected,
+                f: albedo,
+                pdf: 1.0,
+                lobe: Lobe::SPECULAR | Lobe::REFLECTION,
+            });
         }
+
+        let wi = (reflected + uniform_sphere((u[1], u[2])) * self.fuzz).as_unit();
+        let pdf = fuzz_pdf(reflected, self.fuzz, wi);
+        (wi.dot(n) > 0.0 && pdf > 0.0).then(|| BsdfSample {
+            wi,
+            f: albedo * pdf,
+            pdf,
+            lobe: Lobe::GLOSSY | Lobe::REFLECTION,
+        })
+    }
+
+    fn eval(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> Color {
+        if facing_normal(info.n, wo).dot(wi) <= 0.0 {
+            return Color::BLACK;
+        }
+        self.albedo.value(info.u, info.v, info.p) * self.pdf(wo, wi, info)
+    }
+
+    fn is_delta(&self, _info: &HitInfo) -> bool {
+        self.fuzz <= 0.0
+    }
+
+    /// Returns the PDF of the fuzzy reflection, which is `0.0` for the delta lobe.
+    fn pdf(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> f64 {
+        fuzz_pdf(reflect(-wo, info.n), self.fuzz, wi)
+    }
+
+    fn albedo(&self, info: &HitInfo) -> Color {
+        self.albedo.value(info.u, info.v, info.p)
     }
 }
 
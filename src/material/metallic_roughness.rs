@@ -0,0 +1,161 @@
+use std::f64::consts::PI;
+
+use crate::color::Color;
+use crate::geometry::vec3::reflect;
+use crate::geometry::Vec3;
+use crate::material::{facing_normal, Material};
+use crate::shape::HitInfo;
+
+use super::bsdf::{fuzz_pdf, uniform_sphere, BsdfSample, Lobe};
+use super::Texture;
+
+/// A struct to represent metallic-roughness PBR material used by glTF.
+///
+/// The ray is reflected like `Metal` with the probability of metallic,
+/// otherwise it is scattered like `Lambertian`.
+///
+/// # Arguments
+/// * `base_color`          - The base color.
+/// * `metallic`            - The metalness in `[0.0, 1.0]`.
+/// * `roughness`           - The roughness in `[0.0, 1.0]`, which is used as fuzz of reflection.
+/// * `metallic_roughness`  - The optional texture whose G and B channels scale roughness and metallic.
+/// * `emissive`            - The optional emitted color.
+#[derive(Debug)]
+pub struct MetallicRoughness {
+    pub(crate) base_color: Box<dyn Texture>,
+    pub(crate) metallic: f64,
+    pub(crate) roughness: f64,
+    pub(crate) metallic_roughness: Option<Box<dyn Texture>>,
+    pub(crate) emissive: Option<Box<dyn Texture>>,
+}
+
+impl MetallicRoughness {
+    /// Constructs `MetallicRoughness`.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    /// use raytrs::material::{ColorTexture, MetallicRoughness};
+    ///
+    /// let gold = MetallicRoughness::new(
+    ///     Box::new(ColorTexture::new(Color::new(1.0, 0.78, 0.34))),
+    ///     1.0,
+    ///     0.2,
+    /// );
+    /// ```
+    pub fn new(base_color: Box<dyn Texture>, metallic: f64, roughness: f64) -> Self {
+        MetallicRoughness {
+            base_color,
+            metallic,
+            roughness,
+            metallic_roughness: None,
+            emissive: None,
+        }
+    }
+
+    /// Sets the texture to scale roughness by G channel and metallic by B channel.
+    pub fn with_metallic_roughness(mut self, texture: Box<dyn Texture>) -> Self {
+        self.metallic_roughness = Some(texture);
+        self
+    }
+
+    /// Sets the emitted color.
+    pub fn with_emissive(mut self, texture: Box<dyn Texture>) -> Self {
+        self.emissive = Some(texture);
+        self
+    }
+
+    /// Returns metallic and roughness at the hit point.
+    fn factors(&self, info: &HitInfo) -> (f64, f64) {
+        match &self.metallic_roughness {
+            Some(texture) => {
+                let c = texture.value(info.u, info.v, info.p);
+                (self.metallic * c.b, self.roughness * c.g)
+            }
+            None => (self.metallic, self.roughness),
+        }
+    }
+}
+
+impl Material for MetallicRoughness {
+    /// Returns the direction reflected like `Metal` with the probability of metallic,
+    /// otherwise scattered like `Lambertian`.
+    ///
+    /// The metallic lobe is a delta lobe if roughness is zero,
+    /// otherwise the sample is weighted by the PDF of both lobes.
+    fn sample(&self, wo: Vec3, info: &HitInfo, u: [f64; 3]) -> Option<BsdfSample> {
+        let (metallic, roughness) = self.factors(info);
+        let n = facing_normal(info.n, wo);
+
+        let wi = if u[0] < metallic {
+            let reflected = reflect(-wo, n);
+            if roughness <= 0.0 {
+                let albedo = self.base_color.value(info.u, info.v, info.p);
+                return (reflected.dot(n) > 0.0).then_some(BsdfSample {
+                    wi: reflected,
+                    f: albedo * metallic,
+                    pdf: metallic,
+                    lobe: Lobe::SPECULAR | Lobe::REFLECTION,
+                });
+            }
+            (reflected + uniform_sphere((u[1], u[2])) * roughness).as_unit()
+        } else {
+            let scatter_dir = n + uniform_sphere((u[1], u[2]));
+            if scatter_dir.is_close(0.0) {
+                n
+            } else {
+                scatter_dir.as_unit()
+            }
+        };
+        let pdf = self.pdf(wo, wi, info);
+        let lobe = if u[0] < metallic {
+            Lobe::GLOSSY | Lobe::REFLECTION
+        } else {
+            Lobe::DIFFUSE | Lobe::REFLECTION
+        };
+        (wi.dot(n) > 0.0 && pdf > 0.0).then(|| BsdfSample {
+            wi,
+            f: self.eval(wo, wi, info),
+            pdf,
+            lobe,
+        })
+    }
+
+    /// Returns the sum of the diffuse lobe and the glossy metallic lobe weighted by their probabilities.
+    fn eval(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> Color {
+        if facing_normal(info.n, wo).dot(wi) <= 0.0 {
+            return Color::BLACK;
+        }
+        self.base_color.value(info.u, info.v, info.p) * self.pdf(wo, wi, info)
+    }
+
+    /// Returns the PDF of choosing and sampling each lobe, where the delta lobe is excluded.
+    fn pdf(&self, wo: Vec3, wi: Vec3, info: &HitInfo) -> f64 {
+        let (metallic, roughness) = self.factors(info);
+        let metallic = metallic.clamp(0.0, 1.0);
+        let n = facing_normal(info.n, wo);
+        let diffuse = n.dot(wi).max(0.0) / PI;
+        let glossy = fuzz_pdf(reflect(-wo, n), roughness, wi);
+        (1.0 - metallic) * diffuse + metallic * glossy
+    }
+
+    /// Returns `true`, if the surface is fully metallic and smooth.
+    fn is_delta(&self, info: &HitInfo) -> bool {
+        let (metallic, roughness) = self.factors(info);
+        metallic >= 1.0 && roughness <= 0.0
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Vec3) -> Color {
+        self.emissive
+            .as_ref()
+            .map_or(Color::BLACK, |texture| texture.value(u, v, p))
+    }
+
+    fn is_emissive(&self) -> bool {
+        self.emissive.is_some()
+    }
+
+    fn albedo(&self, info: &HitInfo) -> Color {
+        self.base_color.value(info.u, info.v, info.p)
+    }
+}
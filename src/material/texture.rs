@@ -1,10 +1,18 @@
 use std::path::Path;
+use std::sync::Arc;
 
 use crate::color::Color;
 
 use super::Texture;
 use crate::geometry::Vec3;
 
+/// A shared texture, which allows the same texture to be used by several materials.
+impl Texture for Arc<dyn Texture> {
+    fn value(&self, u: f64, v: f64, p: Vec3) -> Color {
+        self.as_ref().value(u, v, p)
+    }
+}
+
 #[derive(Debug)]
 pub struct ColorTexture {
     color: Color,
@@ -51,6 +59,7 @@ pub struct ImageTexture {
     pixels: Vec<Color>,
     width: usize,
     height: usize,
+    repeat: bool,
 }
 
 impl ImageTexture {
@@ -58,7 +67,18 @@ impl ImageTexture {
     where
         P: AsRef<Path>,
     {
-        let img = image::open(path).unwrap().to_rgb8();
+        Self::open(path).unwrap()
+    }
+
+    /// Constructs `ImageTexture` from the image file, and returns error if it fails to load.
+    ///
+    /// # Arguments
+    /// * `path`    - The path of image file.
+    pub fn open<P>(path: P) -> image::ImageResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let img = image::open(path)?.to_rgb8();
         let (w, h) = img.dimensions();
         let mut pixels = vec![Color::BLACK; (w * h) as usize];
         for (c, (_, _, pixel)) in pixels.iter_mut().zip(img.enumerate_pixels()) {
@@ -68,24 +88,133 @@ impl ImageTexture {
                 pixel[2] as f64 / 255.0,
             );
         }
+        Ok(Self::from_pixels(pixels, w as usize, h as usize))
+    }
+
+    /// Constructs `ImageTexture` from pixels in row-major order.
+    ///
+    /// # Arguments
+    /// * `pixels`  - The pixel colors, the first pixel is the top-left corner.
+    /// * `width`   - The image width.
+    /// * `height`  - The image height.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ImageTexture, Texture};
+    ///
+    /// let texture = ImageTexture::from_pixels(vec![Color::RED, Color::BLUE], 2, 1).repeat();
+    /// assert_eq!(texture.value(0.25, 0.0, Vec3::zeros()).to_rgb(), [255, 0, 0]);
+    /// assert_eq!(texture.value(1.75, 0.0, Vec3::zeros()).to_rgb(), [0, 0, 255]);
+    /// ```
+    pub fn from_pixels(pixels: Vec<Color>, width: usize, height: usize) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "pixels must be width * height"
+        );
         Self {
             pixels,
-            width: w as usize,
-            height: h as usize,
+            width,
+            height,
+            repeat: false,
         }
     }
 
+    /// Makes texture coordinates outside of `[0.0, 1.0]` wrap around instead of clamping.
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
     fn sample(&self, u: i64, v: i64) -> Color {
-        let tu = (u as usize).max(0).min(self.width - 1);
-        let tv = (v as usize).max(0).min(self.height - 1);
+        let (tu, tv) = if self.repeat {
+            (
+                u.rem_euclid(self.width as i64) as usize,
+                v.rem_euclid(self.height as i64) as usize,
+            )
+        } else {
+            (
+                (u.max(0) as usize).min(self.width - 1),
+                (v.max(0) as usize).min(self.height - 1),
+            )
+        };
         self.pixels[tu + self.width * tv]
     }
 }
 
 impl Texture for ImageTexture {
     fn value(&self, u: f64, v: f64, _p: Vec3) -> Color {
-        let x = (u * self.width as f64) as i64;
-        let y = (v * self.height as f64) as i64;
+        let x = (u * self.width as f64).floor() as i64;
+        let y = (v * self.height as f64).floor() as i64;
         self.sample(x, y)
     }
 }
+
+/// A texture to interpolate colors of triangle vertices.
+///
+/// The texture coordinates `u`, `v` are treated as the barycentric coordinates of the hit point.
+#[derive(Debug)]
+pub struct VertexColorTexture {
+    colors: [Color; 3],
+}
+
+impl VertexColorTexture {
+    /// Constructs `VertexColorTexture` from the colors of three vertices.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{Texture, VertexColorTexture};
+    ///
+    /// let texture = VertexColorTexture::new([Color::RED, Color::GREEN, Color::BLUE]);
+    /// let [r, g, b] = texture.value(1.0, 0.0, Vec3::zeros()).to_rgb();
+    /// assert_eq!((r, g, b), (0, 255, 0));
+    /// ```
+    pub fn new(colors: [Color; 3]) -> Self {
+        Self { colors }
+    }
+}
+
+impl Texture for VertexColorTexture {
+    fn value(&self, u: f64, v: f64, _p: Vec3) -> Color {
+        let [c0, c1, c2] = self.colors;
+        c0 * (1.0 - u - v) + c1 * u + c2 * v
+    }
+}
+
+/// A texture to scale another texture by a constant color.
+#[derive(Debug)]
+pub struct ScaleTexture {
+    texture: Box<dyn Texture>,
+    scale: Color,
+}
+
+impl ScaleTexture {
+    /// Constructs `ScaleTexture`.
+    ///
+    /// # Arguments
+    /// * `texture` - The texture to be scaled.
+    /// * `scale`   - The scale for each channel.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, ScaleTexture, Texture};
+    ///
+    /// let texture = ScaleTexture::new(Box::new(ColorTexture::new(Color::WHITE)), Color::RED);
+    /// assert_eq!(texture.value(0.0, 0.0, Vec3::zeros()).to_rgb(), [255, 0, 0]);
+    /// ```
+    pub fn new(texture: Box<dyn Texture>, scale: Color) -> Self {
+        Self { texture, scale }
+    }
+}
+
+impl Texture for ScaleTexture {
+    fn value(&self, u: f64, v: f64, p: Vec3) -> Color {
+        self.texture.value(u, v, p) * self.scale
+    }
+}
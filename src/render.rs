@@ -1,47 +1,164 @@
-use image::{Rgb, RgbImage};
+pub(crate) mod adaptive;
+pub(crate) mod aov;
+pub(crate) mod bdpt;
+pub(crate) mod checkpoint;
+pub(crate) mod denoise;
+pub(crate) mod film;
+pub(crate) mod integrator;
+pub(crate) mod options;
+pub(crate) mod photon;
+pub(crate) mod progressive;
+pub(crate) mod stats;
+pub(crate) mod tile;
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+use image::RgbImage;
 use rayon::prelude::*;
 
 use crate::{
     camera::Camera,
     color::Color,
+    filter::{BoxFilter, Filter, FilterType},
     geometry::Vec3,
     interval::Interval,
+    light::{EnvironmentMap, Light, LightSampler, LightSamplerType, Sky},
     ray::Ray,
-    shape::{Shape, ShapeList},
+    sampler::{Dimension, PixelSample, Sampler, SamplerType},
+    shape::{Emitter, HitInfo, Shape, ShapeList},
+    utils::{random, Rng},
 };
 
+use self::adaptive::AdaptiveSampling as _AdaptiveSampling;
+use self::adaptive::PixelStats;
+use self::aov::Aov as _Aov;
+use self::aov::AovBuffer as _AovBuffer;
+use self::aov::AovSample;
+use self::checkpoint::Checkpoint as _Checkpoint;
+use self::denoise::Denoiser as _Denoiser;
+use self::film::Film as _Film;
+use self::film::FilmTile;
+use self::integrator::AlbedoIntegrator as _AlbedoIntegrator;
+use self::integrator::AmbientOcclusionIntegrator as _AmbientOcclusionIntegrator;
+use self::integrator::BidirectionalIntegrator as _BidirectionalIntegrator;
+use self::integrator::BvhCostIntegrator as _BvhCostIntegrator;
+use self::integrator::DepthIntegrator as _DepthIntegrator;
+use self::integrator::IntegratorType as _IntegratorType;
+use self::integrator::NormalIntegrator as _NormalIntegrator;
+use self::integrator::PathIntegrator as _PathIntegrator;
+use self::integrator::PhotonEmission as _PhotonEmission;
+use self::integrator::PhotonMappingIntegrator as _PhotonMappingIntegrator;
+use self::integrator::UvIntegrator as _UvIntegrator;
+use self::integrator::WhittedIntegrator as _WhittedIntegrator;
+use self::options::CancelToken as _CancelToken;
+use self::options::Progress as _Progress;
+use self::options::ProgressTracker;
+use self::options::RenderOptions as _RenderOptions;
+use self::photon::PhotonMap;
+use self::photon::PhotonSettings as _PhotonSettings;
+use self::progressive::Budget as _Budget;
+use self::progressive::ProgressiveRenderer as _ProgressiveRenderer;
+use self::stats::RenderStats as _RenderStats;
+use self::tile::Tile as _Tile;
+use self::tile::TileOrder as _TileOrder;
+
+pub type AdaptiveSampling = _AdaptiveSampling;
+pub type AlbedoIntegrator = _AlbedoIntegrator;
+pub type AmbientOcclusionIntegrator = _AmbientOcclusionIntegrator;
+pub type Aov = _Aov;
+pub type AovBuffer = _AovBuffer;
+pub type BidirectionalIntegrator = _BidirectionalIntegrator;
+pub type Budget = _Budget;
+pub type BvhCostIntegrator = _BvhCostIntegrator;
+pub type CancelToken = _CancelToken;
+pub type Checkpoint = _Checkpoint;
+pub type Denoiser = _Denoiser;
+pub type DepthIntegrator = _DepthIntegrator;
+pub type Film = _Film;
+pub type IntegratorType = _IntegratorType;
+pub type NormalIntegrator = _NormalIntegrator;
+pub type PathIntegrator = _PathIntegrator;
+pub type PhotonEmission = _PhotonEmission;
+pub type PhotonMappingIntegrator = _PhotonMappingIntegrator;
+pub type PhotonSettings = _PhotonSettings;
+pub type Progress = _Progress;
+pub type ProgressiveRenderer<'s> = _ProgressiveRenderer<'s>;
+pub type RenderOptions<'a> = _RenderOptions<'a>;
+pub type RenderStats = _RenderStats;
+pub type Tile = _Tile;
+pub type TileOrder = _TileOrder;
+pub type UvIntegrator = _UvIntegrator;
+pub type WhittedIntegrator = _WhittedIntegrator;
+
 /// Represents the rendering mode.
 ///
 /// # Variants
-/// * `BASIC`   - Basic mode.
-/// * `AA`      - Anti-Aliasing mode.
+/// * `BASIC`       - Basic mode.
+/// * `AA`          - Anti-Aliasing mode.
+/// * `ADAPTIVE`    - Anti-Aliasing mode, whose number of samples is adapted to the noise of each pixel.
+/// * `WHITTED`     - Basic mode traced by `WhittedIntegrator` instead of the integrator of the options.
 pub enum RenderMode {
     BASIC,
     AA(usize),
+    ADAPTIVE(AdaptiveSampling),
+    WHITTED,
 }
 
 /// A trait to render scene.
+///
+/// `Scene` estimates colors by the integrator of `RenderOptions`, which is the path tracer bounced at most `max_depth` times by default.
+/// The shading by normals of earlier versions is `IntegratorType::Normal`.
+///
+/// The first row of the image is the top of the view as image files expect,
+/// so `v` of `Camera::ray` decreases from `1.0` at the top to `0.0` at the bottom.
+/// Before the command-line renderer, the first row was `v = 0.0` and images were upside down.
+///
+/// # Examples
+/// ```
+/// use raytrs::camera::Camera;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::render::{Renderer, Scene};
+///
+/// let scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     4,
+///     4,
+/// ));
+/// // The background is white at the bottom and blue at the top.
+/// let img = scene.render_basic();
+/// assert!(img.get_pixel(0, 0)[0] < img.get_pixel(0, 3)[0]);
+/// ```
 pub trait Renderer {
     fn camera(&self) -> &Camera;
-    fn trace(&self, ray: Ray) -> Color;
 
     /// Render scene with basic mode.
-    fn render_basic(&self) -> RgbImage;
+    fn render_basic(&self) -> RgbImage {
+        self.render(RenderMode::BASIC)
+    }
 
     /// Render scene with Anti Aliasing mode.
     ///
     /// 1. Sampling colors at each pixel around it.
     /// 2. Meaning color.
-    fn render_aa(&self, samples: usize) -> RgbImage;
+    fn render_aa(&self, samples: usize) -> RgbImage {
+        self.render(RenderMode::AA(samples))
+    }
 
     /// Render scene with specified rendering mode.
     fn render(&self, mode: RenderMode) -> RgbImage {
-        match mode {
-            RenderMode::BASIC => self.render_basic(),
-            RenderMode::AA(samples) => self.render_aa(samples),
-        }
+        self.render_with(&RenderOptions::new(mode))
     }
 
+    /// Render scene with options to report progress and to cancel.
+    ///
+    /// If cancelled, the tiles which are not rendered yet are left black.
+    fn render_with(&self, options: &RenderOptions) -> RgbImage;
+
     fn width(&self) -> u32 {
         self.camera().width
     }
@@ -55,25 +172,774 @@ pub trait Renderer {
     }
 }
 
+/// A trait to estimate the color of camera rays, which is passed to `Scene` to render.
+///
+/// Besides the light transport algorithms, debug integrators show properties of the scene,
+/// so integrators can be switched without editing the scene.
+pub trait Integrator: Sync + Send + Debug {
+    /// Returns the color of the camera ray.
+    ///
+    /// # Arguments
+    /// * `scene`   - The scene to render.
+    /// * `ray`     - The ray from camera.
+    /// * `sample`  - The context of the camera sample.
+    fn li(&self, scene: &Scene, ray: Ray, sample: &mut SampleContext) -> Color;
+
+    /// Returns when photon maps are emitted for the integrator, or `None` by default.
+    fn photon_emission(&self) -> Option<PhotonEmission> {
+        None
+    }
+}
+
+/// The context of a camera sample passed to `Integrator`.
+///
+/// Bidirectional path tracing adds the light traced to the camera to the tile,
+/// and photon mapping gathers photons from the photon map of the pass.
+/// Integrators draw the samples of lights and BSDFs from the sampler of the scene by `sample_2d`,
+/// while the random generator is left for the decisions which need not be stratified, e.g. Russian roulette.
+pub struct SampleContext<'a, 'f> {
+    tile: &'a mut FilmTile<'f>,
+    photons: Option<&'a PhotonMap>,
+    sample: PixelSample<'a>,
+    rng: &'a mut Rng,
+}
+
+impl SampleContext<'_, '_> {
+    /// Returns the 2D sample of the dimension from the sampler of the scene.
+    pub fn sample_2d(&self, dimension: Dimension) -> (f64, f64) {
+        self.sample.get(dimension)
+    }
+
+    /// Returns the random generator of the sample.
+    pub fn rng(&mut self) -> &mut Rng {
+        self.rng
+    }
+}
+
+/// The default maximum number of ray bounces.
+pub const DEFAULT_MAX_DEPTH: u32 = 50;
+
+/// The minimum ray parameter to avoid the ray hits the surface where it is scattered from.
+const T_MIN: f64 = 1e-3;
+
+/// The default width and height of tiles.
+pub const DEFAULT_TILE_SIZE: u32 = 32;
+
+/// The color of rays missing all objects.
+///
+/// # Variants
+/// * `Gradient`    - The gradient from white at the bottom to blue at the top.
+/// * `Sky`         - The analytic daylight sky.
+/// * `Environment` - The environment map, which is also sampled as a light.
+enum Background {
+    Gradient,
+    Sky(Sky),
+    Environment(EnvironmentMap),
+}
+
 /// A struct to represent scene to render.
 pub struct Scene {
     camera: Camera,
     world: ShapeList,
+    max_depth: u32,
+    roulette_depth: Option<u32>,
+    clamp: Option<f64>,
+    seed: u64,
+    photon_settings: PhotonSettings,
+    sampler: SamplerType,
+    filter: Box<dyn Filter>,
+    tile_size: u32,
+    tile_order: TileOrder,
+    // The IDs of materials keyed by their addresses.
+    material_ids: HashMap<usize, u32>,
+    // The copies of emissive primitives to sample lights.
+    emitters: Vec<Box<dyn Emitter>>,
+    light_sampler_type: LightSamplerType,
+    // The light sampler of emitters, which is built at the first use after emitters are changed.
+    light_sampler: OnceLock<Box<dyn LightSampler>>,
+    // The light sampler of emitters to start light subpaths, which does not depend on the point.
+    emission_sampler: OnceLock<Box<dyn LightSampler>>,
+    lights: Vec<Box<dyn Light>>,
+    background: Background,
 }
 
 impl Scene {
     pub fn new(camera: Camera) -> Self {
         let world = ShapeList::new();
-        Self { camera, world }
+        Self {
+            camera,
+            world,
+            max_depth: DEFAULT_MAX_DEPTH,
+            roulette_depth: None,
+            clamp: None,
+            seed: 0,
+            photon_settings: PhotonSettings::default(),
+            sampler: SamplerType::default(),
+            filter: FilterType::default().build(None),
+            tile_size: DEFAULT_TILE_SIZE,
+            tile_order: TileOrder::default(),
+            material_ids: HashMap::new(),
+            emitters: Vec::new(),
+            light_sampler_type: LightSamplerType::default(),
+            light_sampler: OnceLock::new(),
+            emission_sampler: OnceLock::new(),
+            lights: Vec::new(),
+            background: Background::Gradient,
+        }
     }
 
     pub fn push(&mut self, object: Box<dyn Shape>) {
+        object.for_each_material(&mut |material| {
+            let next = self.material_ids.len() as u32 + 1;
+            self.material_ids
+                .entry(Arc::as_ptr(material) as *const () as usize)
+                .or_insert(next);
+        });
+        let emitters = object.emitters();
+        if !emitters.is_empty() {
+            self.emitters.extend(emitters);
+            self.light_sampler = OnceLock::new();
+            self.emission_sampler = OnceLock::new();
+        }
         self.world.push(object)
     }
 
+    /// Adds the light which is not geometry, e.g. `PointLight`.
+    pub fn add_light(&mut self, light: Box<dyn Light>) {
+        self.lights.push(light);
+    }
+
+    /// Sets the analytic sky as the background instead of the gradient, and adds its sun as a light.
+    pub fn set_sky(&mut self, sky: Sky) {
+        self.add_light(Box::new(sky.sun()));
+        self.background = Background::Sky(sky);
+    }
+
+    /// Sets the environment map as the background instead of the gradient,
+    /// which is also sampled as a light at each hit.
+    pub fn set_environment(&mut self, environment: EnvironmentMap) {
+        self.background = Background::Environment(environment);
+    }
+
+    /// Sets the maximum number of ray bounces.
+    pub fn set_max_depth(&mut self, max_depth: u32) {
+        self.max_depth = max_depth;
+    }
+
+    /// Returns the maximum number of ray bounces.
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Sets the number of bounces after which the path tracer terminates paths by Russian roulette,
+    /// or `None` to disable it, which is the default.
+    ///
+    /// Paths are terminated with the probability decreasing with their throughput,
+    /// and the surviving paths are weighted up, so the image is unbiased while `max_depth` is still the limit.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::camera::Camera;
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::render::{RenderMode, RenderOptions, Scene};
+    /// use raytrs::shape::Sphere;
+    ///
+    /// let mut scene = Scene::new(Camera::from_lookat(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     90.0,
+    ///     8,
+    ///     8,
+    /// ));
+    /// // The camera is inside the sphere, so paths never escape.
+    /// scene.push(Box::new(Sphere::new(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     10.0,
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+    /// )));
+    /// scene.set_russian_roulette(Some(1));
+    ///
+    /// let film = scene.render_film(&RenderOptions::new(RenderMode::AA(4)));
+    /// assert!(film.stats().terminated > 0);
+    /// ```
+    pub fn set_russian_roulette(&mut self, min_depth: Option<u32>) {
+        self.roulette_depth = min_depth;
+    }
+
+    /// Returns the number of bounces after which paths are terminated by Russian roulette, or `None` if disabled.
+    pub fn russian_roulette(&self) -> Option<u32> {
+        self.roulette_depth
+    }
+
+    /// Sets the maximum luminance of each sample to suppress fireflies, or `None` not to clamp, which is the default.
+    ///
+    /// The color of the brighter sample is scaled down keeping its chromaticity, which biases the image darker.
+    pub fn set_clamp(&mut self, max_luminance: Option<f64>) {
+        self.clamp = max_luminance;
+    }
+
+    /// Returns the maximum luminance of each sample, or `None` if samples are not clamped.
+    pub fn clamp(&self) -> Option<f64> {
+        self.clamp
+    }
+
+    /// Sets the seed of random generators, which is `0` by default.
+    ///
+    /// Each sample has its own random generator seeded from `seed`, the pixel position and the sample index,
+    /// so the rendered image does not depend on the number of threads.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::camera::Camera;
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::render::{RenderMode, Renderer, Scene};
+    /// use raytrs::shape::Sphere;
+    ///
+    /// let mut scene = Scene::new(Camera::from_lookat(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     90.0,
+    ///     16,
+    ///     8,
+    /// ));
+    /// scene.push(Box::new(Sphere::new(
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     0.5,
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+    /// )));
+    /// scene.set_seed(42);
+    ///
+    /// let render = |threads| {
+    ///     let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+    ///     pool.install(|| scene.render(RenderMode::AA(4)))
+    /// };
+    /// assert_eq!(render(1), render(3));
+    /// ```
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Returns the seed of random generators.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the parameters of photon mapping, which are used by photon mapping integrators.
+    pub fn set_photon_settings(&mut self, settings: PhotonSettings) {
+        self.photon_settings = settings;
+    }
+
+    /// Returns the parameters of photon mapping.
+    pub fn photon_settings(&self) -> &PhotonSettings {
+        &self.photon_settings
+    }
+
+    /// Sets the type of sampler to place samples in pixels, which is `Independent` by default.
+    pub fn set_sampler(&mut self, sampler: SamplerType) {
+        self.sampler = sampler;
+    }
+
+    /// Returns the type of sampler.
+    pub fn sampler(&self) -> SamplerType {
+        self.sampler
+    }
+
+    /// Sets the strategy to select emissive primitives to sample at each hit, which is `Uniform` by default.
+    pub fn set_light_sampler(&mut self, light_sampler: LightSamplerType) {
+        self.light_sampler_type = light_sampler;
+        self.light_sampler = OnceLock::new();
+    }
+
+    /// Returns the strategy to select emissive primitives.
+    pub fn light_sampler(&self) -> LightSamplerType {
+        self.light_sampler_type
+    }
+
+    /// Sets the reconstruction filter of Anti-Aliasing mode, which is the box filter of radius `0.5` by default.
+    pub fn set_filter(&mut self, filter: Box<dyn Filter>) {
+        self.filter = filter;
+    }
+
+    /// Sets the size and the order of tiles, which are `32` and `Scanline` by default.
+    ///
+    /// Each tile is rendered by a worker, and reported to the progress callback on completion.
+    pub fn set_tiles(&mut self, size: u32, order: TileOrder) {
+        self.tile_size = size.max(1);
+        self.tile_order = order;
+    }
+
+    /// Returns the size of tiles.
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// Returns the order of tiles.
+    pub fn tile_order(&self) -> TileOrder {
+        self.tile_order
+    }
+
+    /// Changes the image resolution keeping the camera FOV.
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        self.camera.resize(width, height);
+    }
+
+    /// Renders scene into `Film`, whose samples are weighted by the reconstruction filter.
+    ///
+    /// Tiles are rendered in parallel and merged in the tile order,
+    /// so the result does not depend on the number of threads.
+    /// If cancelled, the tiles which are not rendered yet are left black.
+    pub fn render_film(&self, options: &RenderOptions) -> Film {
+        let samples = match options.mode() {
+            RenderMode::BASIC | RenderMode::WHITTED => 1,
+            RenderMode::AA(samples) => *samples,
+            RenderMode::ADAPTIVE(params) => params.max_samples,
+        };
+        let sampler = self.sampler.build(samples, self.seed);
+        self.render_pass(options, options.mode(), 0, sampler.as_ref())
+    }
+
+    /// Returns `ProgressiveRenderer` which accumulates passes of `samples_per_pass` samples per pixel.
+    ///
+    /// # Arguments
+    /// * `samples_per_pass`    - The number of samples per pixel in each pass.
+    pub fn progressive(&self, samples_per_pass: usize) -> ProgressiveRenderer<'_> {
+        ProgressiveRenderer::new(self, samples_per_pass)
+    }
+
+    /// Renders scene into `Film` with `mode` instead of the mode of the options,
+    /// where the sample indices of Anti-Aliasing mode start from `offset`.
+    ///
+    /// Photon mapping emits photons for the pass, while progressive photon mapping in Anti-Aliasing mode
+    /// emits photons for each sample per pixel with the shrinking radius.
+    pub(crate) fn render_pass(
+        &self,
+        options: &RenderOptions,
+        mode: &RenderMode,
+        offset: usize,
+        sampler: &dyn Sampler,
+    ) -> Film {
+        let (width, height) = (self.width(), self.height());
+        let mut film = Film::new(width, height);
+        if options.aovs() {
+            film = film.with_aovs();
+        }
+        let tiles = self.tile_order.tiles(width, height, self.tile_size);
+        let filter = match mode {
+            RenderMode::BASIC | RenderMode::WHITTED => &BoxFilter::new(0.5) as &dyn Filter,
+            RenderMode::AA(_) | RenderMode::ADAPTIVE(_) => self.filter.as_ref(),
+        };
+
+        let whitted = WhittedIntegrator::default();
+        let integrator = match mode {
+            RenderMode::WHITTED => &whitted as &dyn Integrator,
+            _ => options.integrator(),
+        };
+
+        match (integrator.photon_emission(), mode) {
+            (Some(PhotonEmission::Sample), RenderMode::AA(samples)) => {
+                let tracker = ProgressTracker::new(options, (tiles.len() * samples) as u32);
+                for s in offset..offset + samples {
+                    if options.is_cancelled() {
+                        break;
+                    }
+                    let photons = self.photon_map(s, self.photon_settings.radius(s));
+                    let pass = Pass {
+                        integrator,
+                        mode: &RenderMode::AA(1),
+                        offset: s,
+                        sampler,
+                        photons: Some(&photons),
+                    };
+                    self.render_tiles(&mut film, &tiles, filter, &pass, &tracker);
+                }
+            }
+            (emission, _) => {
+                let photons =
+                    emission.map(|_| self.photon_map(offset, self.photon_settings.radius));
+                let tracker = ProgressTracker::new(options, tiles.len() as u32);
+                let pass = Pass {
+                    integrator,
+                    mode,
+                    offset,
+                    sampler,
+                    photons: photons.as_ref(),
+                };
+                self.render_tiles(&mut film, &tiles, filter, &pass, &tracker);
+            }
+        }
+        film
+    }
+
+    /// Renders tiles in parallel and merges them into the film in the tile order.
+    fn render_tiles(
+        &self,
+        film: &mut Film,
+        tiles: &[Tile],
+        filter: &dyn Filter,
+        pass: &Pass,
+        tracker: &ProgressTracker,
+    ) {
+        // The number of tiles merged at once per thread.
+        const BATCH: usize = 4;
+
+        for batch in tiles.chunks(BATCH * rayon::current_num_threads()) {
+            let film_tiles = batch
+                .par_iter()
+                .filter_map(|&tile| {
+                    if tracker.is_cancelled() {
+                        return None;
+                    }
+                    let mut film_tile = film.tile((tile.x0, tile.y0), (tile.x1, tile.y1), filter);
+                    for y in tile.y0..tile.y1 {
+                        for x in tile.x0..tile.x1 {
+                            self.render_pixel(&mut film_tile, (x, y), pass);
+                        }
+                    }
+                    tracker.complete(tile);
+                    Some(film_tile)
+                })
+                .collect::<Vec<_>>();
+            for film_tile in film_tiles {
+                film.merge(film_tile);
+            }
+        }
+    }
+
+    /// Splats the samples of the pixel to the tile.
+    fn render_pixel(&self, tile: &mut FilmTile, pixel: (u32, u32), pass: &Pass) {
+        let (x, y) = pixel;
+        let index = y as u64 * self.width() as u64 + x as u64;
+        let mut sample = |s: usize| {
+            let mut rng = Rng::for_sample(self.seed, index, s as u64);
+            let sample = PixelSample::new(pass.sampler, pixel, s as u32);
+            let (dx, dy) = sample.get(Dimension::Pixel);
+            let (fx, fy) = (x as f64 + dx, y as f64 + dy);
+            let ray = self.film_ray(fx, fy);
+            if tile.has_aovs() {
+                tile.add_aov(x, y, self.aov_sample(&ray));
+            }
+            let color = self.trace_sample(ray, tile, pass, sample, &mut rng);
+            tile.add_sample(fx, fy, color);
+            color
+        };
+        let count = match *pass.mode {
+            RenderMode::BASIC | RenderMode::WHITTED => {
+                let mut rng = Rng::for_sample(self.seed, index, 0);
+                let sample = PixelSample::new(pass.sampler, pixel, 0);
+                let (fx, fy) = (x as f64 + 0.5, y as f64 + 0.5);
+                let ray = self.film_ray(fx, fy);
+                if tile.has_aovs() {
+                    tile.add_aov(x, y, self.aov_sample(&ray));
+                }
+                let color = self.trace_sample(ray, tile, pass, sample, &mut rng);
+                tile.add_sample(fx, fy, color);
+                1
+            }
+            RenderMode::AA(samples) => {
+                (pass.offset..pass.offset + samples).for_each(|s| {
+                    sample(s);
+                });
+                samples
+            }
+            RenderMode::ADAPTIVE(params) => {
+                let mut stats = PixelStats::default();
+                while !stats.converged(&params) {
+                    stats.push(sample(stats.count()));
+                }
+                stats.count()
+            }
+        };
+        tile.add_count(x, y, count as u32);
+    }
+
+    /// Returns the color of the camera ray by the integrator of the pass with the context of the sample,
+    /// which is clamped to the maximum luminance if set.
+    fn trace_sample(
+        &self,
+        ray: Ray,
+        tile: &mut FilmTile,
+        pass: &Pass,
+        sample: PixelSample,
+        rng: &mut Rng,
+    ) -> Color {
+        let mut sample = SampleContext {
+            tile,
+            photons: pass.photons,
+            sample,
+            rng,
+        };
+        let color = pass.integrator.li(self, ray, &mut sample);
+        match self.clamp {
+            Some(max) if color.luminance() > max => {
+                tile.stats_mut().clamped += 1;
+                color * (max / color.luminance())
+            }
+            _ => color,
+        }
+    }
+
+    /// Returns the camera ray through the continuous position (`fx`, `fy`) on the film.
+    fn film_ray(&self, fx: f64, fy: f64) -> Ray {
+        // The image row increases downward, while `v` increases upward.
+        let u = fx / self.width() as f64;
+        let v = 1.0 - fy / self.height() as f64;
+        self.camera().ray(u, v)
+    }
+
+    /// Returns the properties of the first hit of the camera ray, or `None` if the ray hits nothing.
+    fn aov_sample(&self, ray: &Ray) -> Option<AovSample> {
+        let (index, info) = self
+            .world
+            .hit_object(ray, Interval::from_val(T_MIN, f64::INFINITY))?;
+        Some(AovSample {
+            depth: (info.p - self.camera.origin).dot(self.camera.forward()),
+            normal: info.n,
+            albedo: info.m.albedo(&info),
+            position: info.p,
+            uv: (info.u, info.v),
+            material_id: self
+                .material_ids
+                .get(&(Arc::as_ptr(&info.m) as *const () as usize))
+                .copied()
+                .unwrap_or(0),
+            object_id: index as u32 + 1,
+        })
+    }
+
+    /// Returns the color of the ray scattered at most `depth` times.
+    ///
+    /// Emitted light is estimated by both sampling emitters at each hit and hitting them by scattered rays,
+    /// which are combined with multiple importance sampling by the power heuristic.
+    /// The environment map is estimated in the same way by sampling it and missing all objects.
+    /// Lights which are not geometry are only sampled at each hit.
+    /// Lights are sampled even if the BSDF sample fails, e.g. below the surface of a layered material.
+    /// The samples of lights and BSDF are drawn from the dimensions of the bounce.
+    ///
+    /// # Arguments
+    /// * `ray`         - The ray.
+    /// * `depth`       - The remaining number of bounces.
+    /// * `beta`        - The throughput of the path from the camera to the ray, which decides Russian roulette.
+    /// * `scatter_pdf` - The PDF of the ray direction if it is sampled from a non-delta lobe of BSDF,
+    ///   where emitted light is weighted against light sampling.
+    /// * `context`     - The context of the sample, which counts paths terminated by Russian roulette.
+    fn ray_color(
+        &self,
+        ray: &Ray,
+        depth: u32,
+        beta: Color,
+        scatter_pdf: Option<f64>,
+        context: &mut SampleContext,
+    ) -> Color {
+        if depth == 0 {
+            return Color::BLACK;
+        }
+        let bounce = self.max_depth - depth;
+        if let Some(hit_info) = self
+            .world
+            .hit(ray, Interval::from_val(T_MIN, f64::INFINITY))
+        {
+            let mut emitted = hit_info.m.emitted(hit_info.u, hit_info.v, hit_info.p);
+            if let (Some(pdf), false) = (scatter_pdf, emitted.is_black()) {
+                emitted *= power_heuristic(pdf, self.emitter_pdf(ray.origin, ray.direction));
+            }
+            let wo = -ray.direction.as_unit();
+            let (u_light, u_lobe) = context.sample_2d(Dimension::Selection(bounce));
+            let u_bsdf = context.sample_2d(Dimension::Bsdf(bounce));
+            // Lights are sampled whether the BSDF scatters the ray or not,
+            // since the sampled lobe may be absorbed while the others still reflect light.
+            let u = context.sample_2d(Dimension::Light(bounce));
+            let direct = self.sample_emitter(ray, &hit_info, u_light, u)
+                + self.sample_environment(ray, &hit_info, u)
+                + self.sample_lights(ray, &hit_info, u);
+            match hit_info
+                .m
+                .sample(wo, &hit_info, [u_lobe, u_bsdf.0, u_bsdf.1])
+            {
+                Some(sample) => {
+                    let scattered = Ray::new(hit_info.p, sample.wi);
+                    let pdf = (!sample.lobe.is_delta()).then_some(sample.pdf);
+                    let mut weight = sample.weight();
+                    if let Some(q) = self.roulette_probability(beta * weight, bounce) {
+                        if random::<f64>(context.rng) < q {
+                            context.tile.stats_mut().terminated += 1;
+                            return emitted + direct;
+                        }
+                        weight /= 1.0 - q;
+                    }
+                    emitted
+                        + direct
+                        + weight
+                            * self.ray_color(&scattered, depth - 1, beta * weight, pdf, context)
+                }
+                None => emitted + direct,
+            }
+        } else {
+            let mut background = self.background(ray.direction);
+            if let (Some(pdf), Background::Environment(environment)) =
+                (scatter_pdf, &self.background)
+            {
+                background *= power_heuristic(pdf, environment.pdf(ray.direction));
+            }
+            background
+        }
+    }
+
+    /// Returns the probability that Russian roulette terminates the path scattered after `bounce` bounces,
+    /// or `None` if the path always continues.
+    ///
+    /// Paths whose throughput `beta` is at least `1.0` in any channel always continue,
+    /// and the others are terminated with the probability at least `0.05`.
+    fn roulette_probability(&self, beta: Color, bounce: u32) -> Option<f64> {
+        let min_depth = self.roulette_depth?;
+        let max = beta.r.max(beta.g).max(beta.b);
+        (bounce >= min_depth && max < 1.0).then(|| (1.0 - max).max(0.05))
+    }
+
+    /// Returns the light arriving at the hit point directly from an emissive primitive chosen by the light sampler,
+    /// weighted against the scattered ray by multiple importance sampling.
+    ///
+    /// # Arguments
+    /// * `ray`     - The ray hitting the point.
+    /// * `info`    - The hit information.
+    /// * `u_light` - The uniform number to choose the emitter.
+    /// * `u`       - The uniform 2D sample of the direction toward the emitter.
+    fn sample_emitter(&self, ray: &Ray, info: &HitInfo, u_light: f64, u: (f64, f64)) -> Color {
+        if self.emitters.is_empty() {
+            return Color::BLACK;
+        }
+        let Some((index, _)) = self.emitter_sampler().sample(info.p, u_light) else {
+            return Color::BLACK;
+        };
+        let emitter = &self.emitters[index];
+        let wo = -ray.direction.as_unit();
+        let wi = emitter.random_direction(info.p, u).as_unit();
+        let f = info.m.eval(wo, wi, info);
+        if f.is_black() {
+            return Color::BLACK;
+        }
+        let light_pdf = self.emitter_pdf(info.p, wi);
+        if light_pdf <= 0.0 {
+            return Color::BLACK;
+        }
+        // The shadow ray reaches the emitter unless another object occludes it.
+        let Some(hit) = self.world.hit(
+            &Ray::new(info.p, wi),
+            Interval::from_val(T_MIN, f64::INFINITY),
+        ) else {
+            return Color::BLACK;
+        };
+        let emitted = hit.m.emitted(hit.u, hit.v, hit.p);
+        let weight = power_heuristic(light_pdf, info.m.pdf(wo, wi, info));
+        f * emitted * (weight / light_pdf)
+    }
+
+    /// Returns the light arriving at the hit point from the direction sampled on the environment map,
+    /// weighted against the scattered ray by multiple importance sampling.
+    fn sample_environment(&self, ray: &Ray, info: &HitInfo, u: (f64, f64)) -> Color {
+        let Background::Environment(environment) = &self.background else {
+            return Color::BLACK;
+        };
+        let (wi, radiance, light_pdf) = environment.sample(u);
+        if light_pdf <= 0.0 {
+            return Color::BLACK;
+        }
+        let wo = -ray.direction.as_unit();
+        let f = info.m.eval(wo, wi, info);
+        if f.is_black() {
+            return Color::BLACK;
+        }
+        // The environment is reached unless any object occludes it.
+        let shadow = Ray::new(info.p, wi);
+        if self
+            .world
+            .hit(&shadow, Interval::from_val(T_MIN, f64::INFINITY))
+            .is_some()
+        {
+            return Color::BLACK;
+        }
+        let weight = power_heuristic(light_pdf, info.m.pdf(wo, wi, info));
+        f * radiance * (weight / light_pdf)
+    }
+
+    /// Returns the PDF in solid angle of sampling `direction` from `origin` by `sample_emitter`,
+    /// which is the sum of PDFs of emissive primitives weighted by the probabilities of choosing them.
+    fn emitter_pdf(&self, origin: Vec3, direction: Vec3) -> f64 {
+        if self.emitters.is_empty() {
+            return 0.0;
+        }
+        let sampler = self.emitter_sampler();
+        self.emitters
+            .iter()
+            .enumerate()
+            .map(|(i, emitter)| {
+                // Most emitters are not in the direction, whose probabilities are not computed.
+                let pdf = emitter.pdf_value(origin, direction);
+                if pdf > 0.0 {
+                    sampler.pmf(origin, i) * pdf
+                } else {
+                    0.0
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the light sampler of emissive primitives, which is built if it is not yet.
+    fn emitter_sampler(&self) -> &dyn LightSampler {
+        self.light_sampler
+            .get_or_init(|| self.light_sampler_type.build(&self.emitters))
+            .as_ref()
+    }
+
+    /// Returns the light sampler to start light subpaths in proportion to the power of emitters,
+    /// which is built if it is not yet.
+    fn emission_sampler(&self) -> &dyn LightSampler {
+        self.emission_sampler
+            .get_or_init(|| LightSamplerType::Power.build(&self.emitters))
+            .as_ref()
+    }
+
+    /// Returns the sum of the light arriving at the hit point directly from all lights,
+    /// each of which is sampled by the uniform 2D sample `u`.
+    fn sample_lights(&self, ray: &Ray, info: &HitInfo, u: (f64, f64)) -> Color {
+        let wo = -ray.direction.as_unit();
+        self.lights
+            .iter()
+            .filter_map(|light| light.sample(info.p, u))
+            .map(|sample| {
+                let f = info.m.eval(wo, sample.wi, info);
+                if f.is_black() {
+                    return Color::BLACK;
+                }
+                let shadow = Ray::new(info.p, sample.wi);
+                let interval = Interval::from_val(T_MIN, sample.distance - T_MIN);
+                if self.world.hit(&shadow, interval).is_some() {
+                    return Color::BLACK;
+                }
+                f * sample.li
+            })
+            .fold(Color::BLACK, |acc, c| acc + c)
+    }
+
+    /// Returns the color of the ray missing all objects.
     fn background(&self, d: Vec3) -> Color {
-        let t = 0.5 * (d.as_unit().y() + 1.0);
-        Color::WHITE.lerp(Color::new(0.5, 0.7, 1.0), t)
+        match &self.background {
+            Background::Gradient => {
+                let t = 0.5 * (d.as_unit().y() + 1.0);
+                Color::WHITE.lerp(Color::new(0.5, 0.7, 1.0), t)
+            }
+            Background::Sky(sky) => sky.radiance(d),
+            Background::Environment(environment) => environment.radiance(d),
+        }
     }
 }
 
@@ -82,55 +948,34 @@ impl Renderer for Scene {
         &self.camera
     }
 
-    fn trace(&self, ray: Ray) -> Color {
-        const REFLECTANCE: f64 = 0.5;
-        // TODO: fix the scatter becomes infinite loop
-        if let Some(hit_info) = self.world.hit(&ray, Interval::new()) {
-            // let direction = hit_info.n + Vec3::rand_unit();
-            // REFLECTANCE * self.trace(Ray::new(hit_info.p, direction))
-            REFLECTANCE
-                * (Color::new(*hit_info.n.x(), *hit_info.n.y(), *hit_info.n.z()) + Color::WHITE)
-        } else {
-            self.background(ray.direction)
-        }
+    fn render_with(&self, options: &RenderOptions) -> RgbImage {
+        self.render_film(options).to_image()
     }
+}
 
-    fn render_basic(&self) -> RgbImage {
-        let mut img = RgbImage::new(self.width(), self.height());
-        img.enumerate_pixels_mut()
-            .collect::<Vec<(u32, u32, &mut Rgb<u8>)>>()
-            .par_iter_mut()
-            .for_each(|(x, y, pixel)| {
-                let u = *x as f64 / (self.width() - 1) as f64;
-                let v = *y as f64 / (self.height() - 1) as f64;
-                let ray = self.camera().ray(u, v);
-                let rgb = self.trace(ray).to_rgb();
-                pixel[0] = rgb[0];
-                pixel[1] = rgb[1];
-                pixel[2] = rgb[2];
-            });
-        img
-    }
+/// The parameters shared by the tiles of a pass.
+///
+/// # Arguments
+/// * `integrator`  - The integrator, which is `WhittedIntegrator` in Whitted mode.
+/// * `mode`        - The render mode.
+/// * `offset`      - The first sample index of Anti-Aliasing mode.
+/// * `sampler`     - The sampler to place samples in pixels.
+/// * `photons`     - The photon map, if the integrator uses photons.
+struct Pass<'p> {
+    integrator: &'p dyn Integrator,
+    mode: &'p RenderMode,
+    offset: usize,
+    sampler: &'p dyn Sampler,
+    photons: Option<&'p PhotonMap>,
+}
 
-    fn render_aa(&self, samples: usize) -> RgbImage {
-        let mut img = RgbImage::new(self.width(), self.height());
-        img.enumerate_pixels_mut()
-            .collect::<Vec<(u32, u32, &mut Rgb<u8>)>>()
-            .par_iter_mut()
-            .for_each(|(x, y, pixel)| {
-                let mut px_color = (0..samples).into_iter().fold(Color::BLACK, |acc, _| {
-                    let r = Vec3::rand();
-                    let u = (*x as f64 + r.x()) / (self.width() - 1) as f64;
-                    let v = (*y as f64 + r.y()) / (self.height() - 1) as f64;
-                    let ray = self.camera().ray(u, v);
-                    acc + self.trace(ray)
-                });
-                px_color /= samples as f64;
-                let rgb = px_color.to_rgb();
-                pixel[0] = rgb[0];
-                pixel[1] = rgb[1];
-                pixel[2] = rgb[2];
-            });
-        img
+/// Returns the weight of the sample with the PDF `f` against another strategy with the PDF `g`
+/// by the power heuristic of Veach.
+fn power_heuristic(f: f64, g: f64) -> f64 {
+    let (f2, g2) = (f * f, g * g);
+    if f2 + g2 > 0.0 {
+        f2 / (f2 + g2)
+    } else {
+        1.0
     }
 }
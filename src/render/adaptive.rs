@@ -0,0 +1,78 @@
+use serde::Deserialize;
+
+use crate::color::Color;
+
+/// Parameters of adaptive sampling.
+///
+/// Each pixel takes at least `min_samples` samples, and continues sampling up to `max_samples`
+/// until the standard error of the mean luminance falls below `threshold` relative to the mean.
+///
+/// # Arguments
+/// * `min_samples` - The minimum number of samples per pixel.
+/// * `max_samples` - The maximum number of samples per pixel.
+/// * `threshold`   - The relative error to stop sampling.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveSampling {
+    pub min_samples: usize,
+    pub max_samples: usize,
+    pub threshold: f64,
+}
+
+impl AdaptiveSampling {
+    /// Constructs `AdaptiveSampling`.
+    ///
+    /// # Arguments
+    /// * `min_samples` - The minimum number of samples per pixel, which is at least `2` to estimate variance.
+    /// * `max_samples` - The maximum number of samples per pixel.
+    /// * `threshold`   - The relative error to stop sampling.
+    pub fn new(min_samples: usize, max_samples: usize, threshold: f64) -> Self {
+        let min_samples = min_samples.max(2);
+        AdaptiveSampling {
+            min_samples,
+            max_samples: max_samples.max(min_samples),
+            threshold,
+        }
+    }
+}
+
+/// Running mean and variance of the luminance of samples with Welford's algorithm.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PixelStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl PixelStats {
+    pub(crate) fn push(&mut self, color: Color) {
+        let x = color.luminance();
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true`, if the standard error of the mean is small enough.
+    ///
+    /// The error is relative to the mean, whose lower bound is `0.01` to stop sampling dark pixels.
+    ///
+    /// $$
+    /// \sqrt{\frac{\sigma^2}{n}} \le t \max(\mu, 0.01)
+    /// $$
+    pub(crate) fn converged(&self, params: &AdaptiveSampling) -> bool {
+        if self.count < params.min_samples {
+            return false;
+        }
+        if self.count >= params.max_samples {
+            return true;
+        }
+        let variance = self.m2 / (self.count - 1) as f64;
+        (variance / self.count as f64).sqrt() <= params.threshold * self.mean.max(0.01)
+    }
+}
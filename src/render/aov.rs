@@ -0,0 +1,308 @@
+use std::str::FromStr;
+
+use image::RgbImage;
+use serde::Deserialize;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::sampler::hash;
+
+/// Represents an arbitrary output variable (AOV), which is a property of the first hit of camera rays.
+///
+/// # Variants
+/// * `Depth`       - The linear depth along the camera axis, which is infinite at the background.
+/// * `Normal`      - The world-space normal.
+/// * `Albedo`      - The albedo of the material.
+/// * `Position`    - The world-space position.
+/// * `Uv`          - The texture coordinates.
+/// * `MaterialId`  - The ID of the material, numbered from `1` in the order of objects.
+/// * `ObjectId`    - The ID of the object, which is the index of the object in the scene plus `1`.
+///
+/// IDs are `0` at the background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aov {
+    Depth,
+    Normal,
+    Albedo,
+    Position,
+    Uv,
+    MaterialId,
+    ObjectId,
+}
+
+impl Aov {
+    /// All AOVs.
+    pub const ALL: [Aov; 7] = [
+        Aov::Depth,
+        Aov::Normal,
+        Aov::Albedo,
+        Aov::Position,
+        Aov::Uv,
+        Aov::MaterialId,
+        Aov::ObjectId,
+    ];
+
+    /// Returns the name in snake case, e.g. `material_id`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::Position => "position",
+            Aov::Uv => "uv",
+            Aov::MaterialId => "material_id",
+            Aov::ObjectId => "object_id",
+        }
+    }
+
+    /// Returns the names of channels, which follow the conventions of OpenEXR.
+    pub fn channels(&self) -> &'static [&'static str] {
+        match self {
+            Aov::Depth => &["Z"],
+            Aov::Normal | Aov::Position => &["X", "Y", "Z"],
+            Aov::Albedo => &["R", "G", "B"],
+            Aov::Uv => &["U", "V"],
+            Aov::MaterialId | Aov::ObjectId => &["id"],
+        }
+    }
+}
+
+impl FromStr for Aov {
+    type Err = String;
+
+    /// Parses the AOV from `depth`, `normal`, `albedo`, `position`, `uv`, `material_id` or `object_id`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Aov::ALL
+            .into_iter()
+            .find(|aov| aov.name() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown AOV `{}`, expected one of depth, normal, albedo, position, uv, material_id, object_id",
+                    s
+                )
+            })
+    }
+}
+
+/// The properties of the first hit of a camera ray.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AovSample {
+    pub(crate) depth: f64,
+    pub(crate) normal: Vec3,
+    pub(crate) albedo: Color,
+    pub(crate) position: Vec3,
+    pub(crate) uv: (f64, f64),
+    pub(crate) material_id: u32,
+    pub(crate) object_id: u32,
+}
+
+/// The sums of AOV samples in a pixel.
+#[derive(Debug, Clone)]
+pub(crate) struct AovPixel {
+    samples: u32,
+    hits: u32,
+    depth: f64,
+    normal: Vec3,
+    albedo: Color,
+    position: Vec3,
+    uv: (f64, f64),
+    ids: Option<(u32, u32)>,
+}
+
+impl AovPixel {
+    pub(crate) const ZERO: AovPixel = AovPixel {
+        samples: 0,
+        hits: 0,
+        depth: 0.0,
+        normal: Vec3::splat(0.0),
+        albedo: Color::BLACK,
+        position: Vec3::splat(0.0),
+        uv: (0.0, 0.0),
+        ids: None,
+    };
+
+    /// Adds the sample, or the miss if `None`.
+    ///
+    /// IDs are taken from the first sample, since averaging them is meaningless.
+    pub(crate) fn add(&mut self, sample: Option<AovSample>) {
+        self.samples += 1;
+        let Some(sample) = sample else {
+            self.ids.get_or_insert((0, 0));
+            return;
+        };
+        self.hits += 1;
+        self.depth += sample.depth;
+        self.normal += sample.normal;
+        self.albedo += sample.albedo;
+        self.position += sample.position;
+        self.uv.0 += sample.uv.0;
+        self.uv.1 += sample.uv.1;
+        self.ids
+            .get_or_insert((sample.material_id, sample.object_id));
+    }
+
+    pub(crate) fn merge(&mut self, other: &AovPixel) {
+        self.samples += other.samples;
+        self.hits += other.hits;
+        self.depth += other.depth;
+        self.normal += other.normal;
+        self.albedo += other.albedo;
+        self.position += other.position;
+        self.uv.0 += other.uv.0;
+        self.uv.1 += other.uv.1;
+        self.ids = self.ids.or(other.ids);
+    }
+}
+
+/// A buffer of AOVs rendered along with `Film`.
+///
+/// Normal and albedo are averaged over all samples in the pixel, where the background is zero,
+/// so that their edges are anti-aliased as the image.
+/// Depth, position and UV are averaged over the samples which hit objects.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use raytrs::camera::Camera;
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::material::{ColorTexture, Lambertian};
+/// use raytrs::render::{Aov, RenderMode, RenderOptions, Scene};
+/// use raytrs::shape::Sphere;
+///
+/// let mut scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     16,
+///     8,
+/// ));
+/// scene.push(Box::new(Sphere::new(
+///     Vec3::new(0.0, 0.0, -2.0),
+///     1.0,
+///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+/// )));
+///
+/// let film = scene.render_film(&RenderOptions::new(RenderMode::BASIC).with_aovs());
+/// let aovs = film.aovs().unwrap();
+/// // The center hits the front of the sphere, and the corner sees the background.
+/// assert!((aovs.get(Aov::Depth, 8, 4)[0] - 1.0).abs() < 0.1);
+/// assert_eq!(aovs.get(Aov::ObjectId, 8, 4), vec![1.0]);
+/// assert_eq!(aovs.get(Aov::Albedo, 8, 4), vec![0.5, 0.5, 0.5]);
+/// assert_eq!(aovs.get(Aov::Depth, 0, 0), vec![f64::INFINITY]);
+/// assert_eq!(aovs.get(Aov::MaterialId, 0, 0), vec![0.0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AovBuffer {
+    width: u32,
+    height: u32,
+    pub(crate) pixels: Vec<AovPixel>,
+}
+
+impl AovBuffer {
+    pub(crate) fn new(width: u32, height: u32) -> Self {
+        AovBuffer {
+            width,
+            height,
+            pixels: vec![AovPixel::ZERO; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the values of the AOV at the pixel, whose length is the number of channels.
+    ///
+    /// # Arguments
+    /// * `aov` - The AOV.
+    /// * `x`   - The column of the pixel.
+    /// * `y`   - The row of the pixel.
+    pub fn get(&self, aov: Aov, x: u32, y: u32) -> Vec<f64> {
+        let pixel = &self.pixels[(y * self.width + x) as usize];
+        let all = pixel.samples.max(1) as f64;
+        let hits = pixel.hits.max(1) as f64;
+        let (material_id, object_id) = pixel.ids.unwrap_or((0, 0));
+        match aov {
+            Aov::Depth if pixel.hits == 0 => vec![f64::INFINITY],
+            Aov::Depth => vec![pixel.depth / hits],
+            Aov::Normal => (pixel.normal / all).iter().copied().collect(),
+            Aov::Albedo => {
+                let c = pixel.albedo / all;
+                vec![c.r, c.g, c.b]
+            }
+            Aov::Position => (pixel.position / hits).iter().copied().collect(),
+            Aov::Uv => vec![pixel.uv.0 / hits, pixel.uv.1 / hits],
+            Aov::MaterialId => vec![material_id as f64],
+            Aov::ObjectId => vec![object_id as f64],
+        }
+    }
+
+    /// Returns 8-bit image to visualize the AOV.
+    ///
+    /// The background is black, and the others are mapped as follows.
+    ///
+    /// * Depth is mapped from white at the nearest to dark gray at the farthest.
+    /// * Normal is mapped from `[-1, 1]` to `[0, 1]`.
+    /// * Position is normalized by the bounding box of visible points.
+    /// * IDs are mapped to random colors.
+    pub fn to_image(&self, aov: Aov) -> RgbImage {
+        // The values of pixels where any sample hits objects.
+        let values = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let hit = self.pixels[(y * self.width + x) as usize].hits > 0;
+                hit.then(|| self.get(aov, x, y))
+            })
+            .collect::<Vec<_>>();
+        let (mut min, mut max) = ([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]);
+        for v in values.iter().flatten() {
+            for (c, &v) in v.iter().enumerate() {
+                min[c] = min[c].min(v);
+                max[c] = max[c].max(v);
+            }
+        }
+        let normalize = |v: f64, c: usize| {
+            let range = max[c] - min[c];
+            if range > 0.0 {
+                (v - min[c]) / range
+            } else {
+                0.0
+            }
+        };
+
+        RgbImage::from_fn(self.width, self.height, |x, y| {
+            let color = match &values[(y * self.width + x) as usize] {
+                None => Color::BLACK,
+                Some(v) => match aov {
+                    Aov::Depth => Color::WHITE * (1.0 - 0.9 * normalize(v[0], 0)),
+                    Aov::Normal => Color::new(v[0], v[1], v[2]) * 0.5 + 0.5,
+                    Aov::Albedo => Color::new(v[0], v[1], v[2]),
+                    Aov::Position => {
+                        Color::new(normalize(v[0], 0), normalize(v[1], 1), normalize(v[2], 2))
+                    }
+                    Aov::Uv => Color::new(v[0], v[1], 0.0),
+                    Aov::MaterialId | Aov::ObjectId => id_color(v[0] as u32),
+                },
+            };
+            image::Rgb(color.to_rgb())
+        })
+    }
+}
+
+/// Returns the color to distinguish the ID, which is black for `0`.
+fn id_color(id: u32) -> Color {
+    if id == 0 {
+        return Color::BLACK;
+    }
+    let h = hash(&[id as u64]);
+    let channel = |shift: u32| 0.2 + 0.8 * ((h >> shift) & 0xff) as f64 / 255.0;
+    Color::new(channel(0), channel(8), channel(16))
+}
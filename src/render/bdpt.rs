@@ -0,0 +1,512 @@
+use std::f64::consts::PI;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::material::bsdf::uniform_sphere;
+use crate::ray::Ray;
+use crate::render::{Renderer, SampleContext, Scene, T_MIN};
+use crate::sampler::{Dimension, PixelSample};
+use crate::shape::{HitInfo, Shape};
+
+/// The kind of subpath vertices.
+///
+/// # Variants
+/// * `Camera`  - The pinhole of the camera, which starts camera subpaths.
+/// * `Light`   - The point sampled on an emissive primitive, which starts light subpaths.
+/// * `Surface` - The point where a subpath hits an object.
+enum VertexKind {
+    Camera,
+    Light,
+    Surface(HitInfo),
+}
+
+/// A vertex of camera or light subpaths.
+///
+/// # Arguments
+/// * `kind`    - The kind of the vertex.
+/// * `p`       - The position.
+/// * `n`       - The normal, which is not used for the camera.
+/// * `beta`    - The throughput from the start of the subpath divided by the PDF.
+/// * `delta`   - Whether the direction is scattered by a delta lobe at the vertex.
+/// * `pdf_fwd` - The PDF in area of sampling the vertex from the start of the subpath.
+/// * `pdf_rev` - The PDF in area of sampling the vertex from the other end of the path.
+struct Vertex {
+    kind: VertexKind,
+    p: Vec3,
+    n: Vec3,
+    beta: Color,
+    delta: bool,
+    pdf_fwd: f64,
+    pdf_rev: f64,
+}
+
+impl Vertex {
+    fn new(kind: VertexKind, p: Vec3, n: Vec3, beta: Color, pdf_fwd: f64) -> Self {
+        Vertex {
+            kind,
+            p,
+            n,
+            beta,
+            delta: false,
+            pdf_fwd,
+            pdf_rev: 0.0,
+        }
+    }
+
+    /// Returns `true`, if the vertex is on a surface rather than the pinhole.
+    fn is_on_surface(&self) -> bool {
+        !matches!(self.kind, VertexKind::Camera)
+    }
+
+    /// Converts the PDF in solid angle of the direction toward `next` into the PDF in area at `next`.
+    fn convert_density(&self, pdf: f64, next: &Vertex) -> f64 {
+        let w = next.p - self.p;
+        let dist2 = w.norm_squared();
+        if dist2 == 0.0 {
+            return 0.0;
+        }
+        let cos = if next.is_on_surface() {
+            next.n.dot(w).abs() / dist2.sqrt()
+        } else {
+            1.0
+        };
+        pdf * cos / dist2
+    }
+
+    /// Returns the PDF in area at `next` of emitting light from the vertex toward `next`.
+    fn pdf_emission(&self, next: &Vertex) -> f64 {
+        let w = (next.p - self.p).as_unit();
+        self.convert_density(self.n.dot(w).abs() / (2.0 * PI), next)
+    }
+}
+
+/// The MIS-related values of a vertex, which are overridden for the vertices around the connection.
+#[derive(Clone, Copy)]
+struct VertexPdf {
+    fwd: f64,
+    rev: f64,
+    delta: bool,
+}
+
+impl From<&Vertex> for VertexPdf {
+    fn from(v: &Vertex) -> Self {
+        VertexPdf {
+            fwd: v.pdf_fwd,
+            rev: v.pdf_rev,
+            delta: v.delta,
+        }
+    }
+}
+
+impl Scene {
+    /// Returns the color of the camera ray by bidirectional path tracing,
+    /// and adds the light traced to the camera by the light subpath to the tile.
+    ///
+    /// The camera and the light subpaths are connected by all strategies,
+    /// which are combined with multiple importance sampling by the power heuristic.
+    /// The light subpath starts from an emissive primitive chosen in proportion to its power.
+    /// The background and lights which are not geometry cannot start light subpaths,
+    /// so they are only estimated by the camera subpath missing all objects and sampling lights at each vertex.
+    /// The light subpath draws samples from the dimensions of the bounces after the camera subpath.
+    ///
+    /// # Arguments
+    /// * `ray`     - The ray from camera.
+    /// * `context` - The context of the sample, whose tile receives the light traced to the camera.
+    pub(crate) fn trace_bidirectional(&self, ray: Ray, context: &mut SampleContext) -> Color {
+        if self.max_depth == 0 {
+            return Color::BLACK;
+        }
+        let mut color = Color::BLACK;
+        let max_depth = self.max_depth as usize;
+
+        let mut camera = vec![Vertex::new(
+            VertexKind::Camera,
+            self.camera.origin,
+            self.camera.forward(),
+            Color::WHITE,
+            1.0,
+        )];
+        let sample = context.sample;
+        let pdf = self.camera.pdf_direction(ray.direction);
+        if let Some((beta, direction)) = self.random_walk(
+            ray,
+            Color::WHITE,
+            pdf,
+            max_depth + 2,
+            0,
+            &mut camera,
+            &sample,
+        ) {
+            color += beta * self.background(direction);
+        }
+        let light = self.light_subpath(max_depth + 1, self.max_depth + 1, &sample);
+
+        for t in 1..=camera.len() {
+            if t > 1 && t - 1 <= max_depth {
+                color += self.sample_lights_at(&camera, t, &sample);
+            }
+            for s in 0..=light.len() {
+                if s + t < 2 || s + t - 2 > max_depth {
+                    continue;
+                }
+                if t == 1 {
+                    if let Some((fx, fy, c)) = self.connect_camera(&light, &camera, s) {
+                        context.tile.add_light(fx, fy, c);
+                    }
+                } else {
+                    color += self.connect(&light, &camera, s, t);
+                }
+            }
+        }
+        color
+    }
+
+    /// Extends the subpath by scattering the ray until it has `max_vertices` vertices or is absorbed.
+    /// The BSDF at the `i`-th hit is sampled from the dimensions of the bounce `first_bounce + i`.
+    ///
+    /// Returns the throughput and the direction of the ray, if the ray misses all objects.
+    #[allow(clippy::too_many_arguments)]
+    fn random_walk(
+        &self,
+        mut ray: Ray,
+        mut beta: Color,
+        mut pdf: f64,
+        max_vertices: usize,
+        first_bounce: u32,
+        path: &mut Vec<Vertex>,
+        sample: &PixelSample,
+    ) -> Option<(Color, Vec3)> {
+        while path.len() < max_vertices {
+            let Some(info) = self
+                .world
+                .hit(&ray, Interval::from_val(T_MIN, f64::INFINITY))
+            else {
+                return Some((beta, ray.direction));
+            };
+            let (p, n) = (info.p, info.n);
+            let mut vertex = Vertex::new(VertexKind::Surface(info), p, n, beta, 0.0);
+            let prev = path.len() - 1;
+            vertex.pdf_fwd = path[prev].convert_density(pdf, &vertex);
+            path.push(vertex);
+            if path.len() >= max_vertices {
+                break;
+            }
+
+            let VertexKind::Surface(info) = &path[prev + 1].kind else {
+                unreachable!();
+            };
+            let wo = -ray.direction.as_unit();
+            let bounce = first_bounce + prev as u32;
+            let (_, u_lobe) = sample.get(Dimension::Selection(bounce));
+            let u = sample.get(Dimension::Bsdf(bounce));
+            let bsdf = info.m.sample(wo, info, [u_lobe, u.0, u.1])?;
+            beta *= bsdf.weight();
+            if beta.is_black() {
+                break;
+            }
+            let delta = bsdf.lobe.is_delta();
+            let (pdf_fwd, pdf_rev) = if delta {
+                (0.0, 0.0)
+            } else {
+                (bsdf.pdf, info.m.pdf(bsdf.wi, wo, info))
+            };
+            let rev = path[prev + 1].convert_density(pdf_rev, &path[prev]);
+            path[prev].pdf_rev = rev;
+            path[prev + 1].delta = delta;
+            ray = Ray::new(p, bsdf.wi);
+            pdf = pdf_fwd;
+        }
+        None
+    }
+
+    /// Returns the light subpath of at most `max_vertices` vertices starting from an emissive primitive,
+    /// or the empty path if there is no emitter.
+    ///
+    /// The emission is sampled from the dimensions of `bounce`, and the following hits from the next bounces.
+    fn light_subpath(&self, max_vertices: usize, bounce: u32, sample: &PixelSample) -> Vec<Vertex> {
+        let mut path = Vec::new();
+        let (u_light, _) = sample.get(Dimension::Selection(bounce));
+        let Some((vertex, direction, pdf)) = self.sample_emission_ray(sample, bounce, u_light)
+        else {
+            return path;
+        };
+        let beta = vertex.beta * (vertex.n.dot(direction).abs() / pdf);
+        let ray = Ray::new(vertex.p, direction);
+        path.push(vertex);
+        self.random_walk(ray, beta, pdf, max_vertices, bounce + 1, &mut path, sample);
+        path
+    }
+
+    /// Returns the ray emitted from an emissive primitive chosen in proportion to its power
+    /// and its throughput, which is the emitted radiance times the cosine divided by the PDFs.
+    ///
+    /// # Arguments
+    /// * `sample`  - The sample to draw the point and the direction from the dimensions of `bounce`.
+    /// * `bounce`  - The bounce of the dimensions.
+    /// * `u_light` - The uniform number to choose the emitter.
+    pub(super) fn sample_emitter_ray(
+        &self,
+        sample: &PixelSample,
+        bounce: u32,
+        u_light: f64,
+    ) -> Option<(Ray, Color)> {
+        let (vertex, direction, pdf) = self.sample_emission_ray(sample, bounce, u_light)?;
+        let beta = vertex.beta * (vertex.n.dot(direction).abs() / pdf);
+        Some((Ray::new(vertex.p, direction), beta))
+    }
+
+    /// Returns the vertex on an emissive primitive chosen by `u_light`,
+    /// and the direction emitted from the vertex with its PDF in solid angle.
+    ///
+    /// The point is sampled from the light dimension of `bounce`, the side from the second selection dimension
+    /// and the direction from the BSDF dimension.
+    fn sample_emission_ray(
+        &self,
+        sample: &PixelSample,
+        bounce: u32,
+        u_light: f64,
+    ) -> Option<(Vertex, Vec3, f64)> {
+        let vertex = self.sample_light_vertex(u_light, sample.get(Dimension::Light(bounce)))?;
+        let (_, u_side) = sample.get(Dimension::Selection(bounce));
+        let (direction, pdf) =
+            sample_emission(vertex.n, u_side, sample.get(Dimension::Bsdf(bounce)))?;
+        Some((vertex, direction, pdf))
+    }
+
+    /// Returns the vertex on an emissive primitive chosen by `u_light` in proportion to its power
+    /// at the point sampled by `u`, whose throughput is the emitted radiance divided by the PDF in area.
+    fn sample_light_vertex(&self, u_light: f64, u: (f64, f64)) -> Option<Vertex> {
+        if self.emitters.is_empty() {
+            return None;
+        }
+        let (index, pmf) = self.emission_sampler().sample(Vec3::zeros(), u_light)?;
+        let (info, pdf_area) = self.emitters[index].sample_surface(u)?;
+        let emitted = info.m.emitted(info.u, info.v, info.p);
+        let pdf = pmf * pdf_area;
+        if emitted.is_black() || pdf <= 0.0 {
+            return None;
+        }
+        Some(Vertex::new(
+            VertexKind::Light,
+            info.p,
+            info.n,
+            emitted / pdf,
+            pdf,
+        ))
+    }
+
+    /// Returns the PDF in area of sampling the emissive vertex `v` by `sample_light_vertex`,
+    /// where `prev` is the vertex which `v` is seen from.
+    fn pdf_light_origin(&self, v: &Vertex, prev: &Vertex) -> f64 {
+        // The ray reaches `v` at `t = 1`, so only the emitters containing `v` are hit around it.
+        const EPSILON: f64 = 1e-6;
+
+        let ray = Ray::new(prev.p, v.p - prev.p);
+        let interval = Interval::from_val(1.0 - EPSILON, 1.0 + EPSILON);
+        let sampler = self.emission_sampler();
+        self.emitters
+            .iter()
+            .enumerate()
+            .filter(|(_, emitter)| emitter.hit(&ray, interval).is_some())
+            .map(|(i, emitter)| sampler.pmf(Vec3::zeros(), i) / emitter.area())
+            .sum()
+    }
+
+    /// Returns the PDF in area at `next` of scattering the ray from `prev` at `v` toward `next`.
+    fn pdf_vertex(&self, v: &Vertex, prev: Option<&Vertex>, next: &Vertex) -> f64 {
+        match &v.kind {
+            VertexKind::Camera => v.convert_density(self.camera.pdf_direction(next.p - v.p), next),
+            VertexKind::Light => v.pdf_emission(next),
+            VertexKind::Surface(info) => {
+                let Some(prev) = prev else {
+                    return 0.0;
+                };
+                let wo = (prev.p - v.p).as_unit();
+                let wi = (next.p - v.p).as_unit();
+                v.convert_density(info.m.pdf(wo, wi, info), next)
+            }
+        }
+    }
+
+    /// Returns BSDF times the cosine at the vertex `v` scattering the light from `prev` toward `w`,
+    /// or the cosine for emitters whose radiance is included in the throughput.
+    fn eval_vertex(&self, v: &Vertex, prev: Option<&Vertex>, w: Vec3) -> Color {
+        match &v.kind {
+            VertexKind::Camera => Color::BLACK,
+            VertexKind::Light => Color::WHITE * v.n.dot(w).abs(),
+            VertexKind::Surface(info) => match prev {
+                Some(prev) => info.m.eval((prev.p - v.p).as_unit(), w, info),
+                None => Color::BLACK,
+            },
+        }
+    }
+
+    /// Returns `true`, if no object occludes the segment between `a` and `b`.
+    fn unoccluded(&self, a: Vec3, b: Vec3) -> bool {
+        let d = b - a;
+        let dist = d.norm();
+        let ray = Ray::new(a, d / dist);
+        self.world
+            .hit(&ray, Interval::from_val(T_MIN, dist - T_MIN))
+            .is_none()
+    }
+
+    /// Returns the light of the strategy with `s` light vertices and `t >= 2` camera vertices,
+    /// weighted by multiple importance sampling.
+    ///
+    /// If `s == 0`, the camera subpath hits an emitter by itself.
+    fn connect(&self, light: &[Vertex], camera: &[Vertex], s: usize, t: usize) -> Color {
+        let qt = &camera[t - 1];
+        let qt_prev = &camera[t - 2];
+        if s == 0 {
+            let VertexKind::Surface(info) = &qt.kind else {
+                return Color::BLACK;
+            };
+            let emitted = info.m.emitted(info.u, info.v, info.p);
+            if emitted.is_black() {
+                return Color::BLACK;
+            }
+            let overrides = [
+                self.pdf_light_origin(qt, qt_prev),
+                qt.pdf_emission(qt_prev),
+                0.0,
+                0.0,
+            ];
+            return qt.beta * emitted * self.mis_weight(light, camera, s, t, overrides);
+        }
+
+        let ps = &light[s - 1];
+        let ps_prev = s.checked_sub(2).map(|i| &light[i]);
+        let d = ps.p - qt.p;
+        let dist2 = d.norm_squared();
+        if dist2 == 0.0 {
+            return Color::BLACK;
+        }
+        let w = d / dist2.sqrt();
+        let color = qt.beta
+            * self.eval_vertex(qt, Some(qt_prev), w)
+            * self.eval_vertex(ps, ps_prev, -w)
+            * ps.beta
+            / dist2;
+        if color.is_black() || !self.unoccluded(qt.p, ps.p) {
+            return Color::BLACK;
+        }
+        let overrides = [
+            self.pdf_vertex(ps, ps_prev, qt),
+            self.pdf_vertex(qt, Some(ps), qt_prev),
+            self.pdf_vertex(qt, Some(qt_prev), ps),
+            ps_prev.map_or(0.0, |prev| self.pdf_vertex(ps, Some(qt), prev)),
+        ];
+        color * self.mis_weight(light, camera, s, t, overrides)
+    }
+
+    /// Returns the continuous position on the film and the light of the strategy
+    /// connecting `s` light vertices to the camera, weighted by multiple importance sampling.
+    fn connect_camera(
+        &self,
+        light: &[Vertex],
+        camera: &[Vertex],
+        s: usize,
+    ) -> Option<(f64, f64, Color)> {
+        let cam = &camera[0];
+        let ps = &light[s - 1];
+        let ps_prev = s.checked_sub(2).map(|i| &light[i]);
+        let (u, v) = self.camera.project(ps.p)?;
+        let d = cam.p - ps.p;
+        let dist2 = d.norm_squared();
+        // The importance of the camera times the cosine is the PDF of the direction.
+        let importance = self.camera.pdf_direction(-d) / dist2;
+        let color = ps.beta * self.eval_vertex(ps, ps_prev, d / dist2.sqrt()) * importance;
+        if color.is_black() || !self.unoccluded(ps.p, cam.p) {
+            return None;
+        }
+        let overrides = [
+            self.pdf_vertex(ps, ps_prev, cam),
+            0.0,
+            self.pdf_vertex(cam, None, ps),
+            ps_prev.map_or(0.0, |prev| self.pdf_vertex(ps, Some(cam), prev)),
+        ];
+        let weight = self.mis_weight(light, camera, s, 1, overrides);
+        let (fx, fy) = (u * self.width() as f64, (1.0 - v) * self.height() as f64);
+        Some((fx, fy, color * weight))
+    }
+
+    /// Returns the weight of the strategy with `s` light vertices and `t` camera vertices
+    /// by the power heuristic over all strategies to sample the same path.
+    ///
+    /// # Arguments
+    /// * `overrides`   - The reverse PDFs of the camera endpoint, its predecessor,
+    ///   the light endpoint and its predecessor, which depend on the connection.
+    fn mis_weight(
+        &self,
+        light: &[Vertex],
+        camera: &[Vertex],
+        s: usize,
+        t: usize,
+        overrides: [f64; 4],
+    ) -> f64 {
+        let mut light = light[..s].iter().map(VertexPdf::from).collect::<Vec<_>>();
+        let mut camera = camera[..t].iter().map(VertexPdf::from).collect::<Vec<_>>();
+        camera[t - 1].rev = overrides[0];
+        camera[t - 1].delta = false;
+        if t > 1 {
+            camera[t - 2].rev = overrides[1];
+        }
+        if s > 0 {
+            light[s - 1].rev = overrides[2];
+            light[s - 1].delta = false;
+        }
+        if s > 1 {
+            light[s - 2].rev = overrides[3];
+        }
+
+        // The PDFs of delta lobes are zero, which are cancelled out.
+        let remap = |pdf: f64| if pdf != 0.0 { pdf * pdf } else { 1.0 };
+        let mut sum = 0.0;
+        let mut ratio = 1.0;
+        for i in (1..t).rev() {
+            ratio *= remap(camera[i].rev) / remap(camera[i].fwd);
+            if !camera[i].delta && !camera[i - 1].delta {
+                sum += ratio;
+            }
+        }
+        ratio = 1.0;
+        for i in (0..s).rev() {
+            ratio *= remap(light[i].rev) / remap(light[i].fwd);
+            let delta_prev = i > 0 && light[i - 1].delta;
+            if !light[i].delta && !delta_prev {
+                sum += ratio;
+            }
+        }
+        1.0 / (1.0 + sum)
+    }
+
+    /// Returns the light arriving at the `t`-th camera vertex directly from the lights which are not geometry.
+    fn sample_lights_at(&self, camera: &[Vertex], t: usize, sample: &PixelSample) -> Color {
+        let (qt, qt_prev) = (&camera[t - 1], &camera[t - 2]);
+        match &qt.kind {
+            VertexKind::Surface(info) if !self.lights.is_empty() => {
+                let ray = Ray::new(qt_prev.p, qt.p - qt_prev.p);
+                let u = sample.get(Dimension::Light(t as u32 - 2));
+                qt.beta * self.sample_lights(&ray, info, u)
+            }
+            _ => Color::BLACK,
+        }
+    }
+}
+
+/// Returns the direction emitted from the point with the normal `n` and its PDF in solid angle.
+///
+/// The emitters are Lambertian on both sides, so the direction is cosine-weighted on the side chosen by `u_side`
+/// and sampled by `u`.
+fn sample_emission(n: Vec3, u_side: f64, u: (f64, f64)) -> Option<(Vec3, f64)> {
+    let side = if u_side < 0.5 { n } else { -n };
+    let mut direction = side + uniform_sphere(u);
+    if direction.is_close(0.0) {
+        direction = side;
+    }
+    let direction = direction.as_unit();
+    let cos = side.dot(direction);
+    (cos > 0.0).then_some((direction, cos / (2.0 * PI)))
+}
@@ -0,0 +1,206 @@
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::color::Color;
+use crate::render::{Film, RenderStats};
+use crate::sampler::SamplerType;
+
+/// The magic bytes at the beginning of checkpoint files.
+const MAGIC: &[u8; 8] = b"RAYTRSCK";
+
+/// The version of checkpoint files, which is incremented when the layout changes.
+const VERSION: u32 = 3;
+
+/// A snapshot of progressive rendering to resume later.
+///
+/// Each sample has its own random generator seeded from `seed`, the pixel and the sample index,
+/// so `seed` and the number of passes are the whole random state to continue the sequence.
+///
+/// # Arguments
+/// * `scene_hash`          - The hash to identify the scene and the settings which are not stored here.
+/// * `seed`                - The seed of random generators.
+/// * `sampler`             - The type of sampler.
+/// * `samples_per_pass`    - The number of samples per pixel in each pass.
+/// * `passes`              - The number of completed passes.
+/// * `elapsed`             - The total time spent on the completed passes.
+/// * `film`                - The accumulated film, which includes the sample counts and the statistics.
+///
+/// # Examples
+/// ```
+/// use raytrs::camera::Camera;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::render::{Budget, Checkpoint, ProgressiveRenderer, Scene};
+///
+/// let scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     16,
+///     8,
+/// ));
+///
+/// let mut renderer = scene.progressive(2);
+/// renderer.run(&Budget::passes(2), None, |_| {});
+/// let mut bytes = Vec::new();
+/// renderer.checkpoint(42).write(&mut bytes).unwrap();
+///
+/// let checkpoint = Checkpoint::read(&mut bytes.as_slice()).unwrap();
+/// assert!(ProgressiveRenderer::resume(&scene, checkpoint.clone(), 0).is_err());
+/// let mut resumed = ProgressiveRenderer::resume(&scene, checkpoint, 42).unwrap();
+/// resumed.run(&Budget::passes(1), None, |_| {});
+/// assert_eq!(resumed.passes(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    pub scene_hash: u64,
+    pub seed: u64,
+    pub sampler: SamplerType,
+    pub samples_per_pass: usize,
+    pub passes: u32,
+    pub elapsed: Duration,
+    pub film: Film,
+}
+
+impl Checkpoint {
+    /// Writes the checkpoint in little-endian binary.
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        w.write_all(&self.scene_hash.to_le_bytes())?;
+        w.write_all(&self.seed.to_le_bytes())?;
+        w.write_all(&[sampler_to_u8(self.sampler)])?;
+        w.write_all(&(self.samples_per_pass as u64).to_le_bytes())?;
+        w.write_all(&self.passes.to_le_bytes())?;
+        w.write_all(&self.elapsed.as_secs_f64().to_le_bytes())?;
+        w.write_all(&self.film.width().to_le_bytes())?;
+        w.write_all(&self.film.height().to_le_bytes())?;
+        let stats = self.film.stats();
+        w.write_all(&stats.terminated.to_le_bytes())?;
+        w.write_all(&stats.clamped.to_le_bytes())?;
+        for (sum, weight, count, light) in self.film.raw_pixels() {
+            for v in [sum.r, sum.g, sum.b, weight] {
+                w.write_all(&v.to_le_bytes())?;
+            }
+            w.write_all(&count.to_le_bytes())?;
+            for v in [light.r, light.g, light.b] {
+                w.write_all(&v.to_le_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the checkpoint written by `write`.
+    pub fn read<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a checkpoint file".to_string()));
+        }
+        let version = read_u32(r)?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported checkpoint version {}, expected {}",
+                version, VERSION
+            )));
+        }
+        let scene_hash = read_u64(r)?;
+        let seed = read_u64(r)?;
+        let mut sampler = [0; 1];
+        r.read_exact(&mut sampler)?;
+        let sampler = sampler_from_u8(sampler[0])
+            .ok_or_else(|| invalid_data(format!("unknown sampler {}", sampler[0])))?;
+        let samples_per_pass = read_u64(r)? as usize;
+        let passes = read_u32(r)?;
+        let elapsed =
+            Duration::try_from_secs_f64(read_f64(r)?).map_err(|e| invalid_data(e.to_string()))?;
+        let (width, height) = (read_u32(r)?, read_u32(r)?);
+        let stats = RenderStats {
+            terminated: read_u64(r)?,
+            clamped: read_u64(r)?,
+        };
+        // Not to allocate the size read from a broken file in advance.
+        let mut raw = Vec::new();
+        for _ in 0..width as usize * height as usize {
+            let sum = Color::new(read_f64(r)?, read_f64(r)?, read_f64(r)?);
+            let (weight, count) = (read_f64(r)?, read_u32(r)?);
+            let light = Color::new(read_f64(r)?, read_f64(r)?, read_f64(r)?);
+            raw.push((sum, weight, count, light));
+        }
+        let film = Film::from_raw_pixels(width, height, raw)
+            .ok_or_else(|| invalid_data("invalid film size".to_string()))?
+            .with_stats(stats);
+        Ok(Checkpoint {
+            scene_hash,
+            seed,
+            sampler,
+            samples_per_pass,
+            passes,
+            elapsed,
+            film,
+        })
+    }
+
+    /// Saves the checkpoint to the file.
+    ///
+    /// The checkpoint is written to the temporary file next to `path` and renamed,
+    /// so the previous checkpoint is kept if the process is killed while writing.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut tmp = OsString::from(path.as_os_str());
+        tmp.push(".tmp");
+        let mut w = BufWriter::new(File::create(&tmp)?);
+        self.write(&mut w)?;
+        w.into_inner()?.sync_all()?;
+        fs::rename(&tmp, path)
+    }
+
+    /// Loads the checkpoint from the file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+fn sampler_to_u8(sampler: SamplerType) -> u8 {
+    match sampler {
+        SamplerType::Independent => 0,
+        SamplerType::Stratified => 1,
+        SamplerType::Halton => 2,
+        SamplerType::Sobol => 3,
+        SamplerType::BlueNoise => 4,
+    }
+}
+
+fn sampler_from_u8(value: u8) -> Option<SamplerType> {
+    match value {
+        0 => Some(SamplerType::Independent),
+        1 => Some(SamplerType::Stratified),
+        2 => Some(SamplerType::Halton),
+        3 => Some(SamplerType::Sobol),
+        4 => Some(SamplerType::BlueNoise),
+        _ => None,
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32<R: Read>(r: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    r.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f64<R: Read>(r: &mut R) -> io::Result<f64> {
+    read_u64(r).map(f64::from_bits)
+}
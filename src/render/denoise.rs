@@ -0,0 +1,204 @@
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::render::{Aov, Film};
+
+/// The weights of the B3 spline kernel of the à-trous wavelet transform.
+const KERNEL: [f64; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+/// The minimum albedo to divide colors by, where textures are not removed from colors below it.
+const MIN_ALBEDO: f64 = 1e-3;
+
+/// An edge-avoiding à-trous wavelet denoiser guided by albedo and normal AOVs.
+///
+/// Colors are divided by albedo so that textures are not blurred, and then filtered by a 5x5 kernel
+/// whose taps are spread by `2^i` pixels in the `i`-th iteration.
+/// Each tap is weighted by the similarity of colors, normals and albedos to the center pixel,
+/// where the color tolerance is halved in each iteration as the noise is reduced.
+///
+/// If the film is rendered without AOVs, only colors guide the filter.
+///
+/// # Arguments
+/// * `iterations`      - The number of iterations, which filter the radius of `2^(iterations + 1)` pixels.
+/// * `sigma_color`     - The tolerance of the color difference.
+/// * `sigma_normal`    - The tolerance of the normal difference.
+/// * `sigma_albedo`    - The tolerance of the albedo difference.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use raytrs::camera::Camera;
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::material::{ColorTexture, Lambertian};
+/// use raytrs::render::{Denoiser, RenderMode, RenderOptions, Scene};
+/// use raytrs::shape::Sphere;
+///
+/// let mut scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     16,
+///     8,
+/// ));
+/// scene.push(Box::new(Sphere::new(
+///     Vec3::new(0.0, 0.0, -2.0),
+///     1.0,
+///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+/// )));
+///
+/// let film = scene.render_film(&RenderOptions::new(RenderMode::AA(2)).with_aovs());
+/// let denoised = Denoiser::default().denoise(&film);
+/// assert_eq!((denoised.width(), denoised.height()), (16, 8));
+/// assert_eq!(denoised.total_samples(), film.total_samples());
+/// ```
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Denoiser {
+    pub iterations: u32,
+    pub sigma_color: f64,
+    pub sigma_normal: f64,
+    pub sigma_albedo: f64,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Denoiser {
+            iterations: 5,
+            sigma_color: 0.5,
+            sigma_normal: 0.1,
+            sigma_albedo: 0.1,
+        }
+    }
+}
+
+/// The AOVs of a pixel to guide the filter.
+#[derive(Debug, Clone, Copy)]
+struct Guide {
+    normal: Vec3,
+    albedo: Color,
+}
+
+impl Denoiser {
+    /// Returns the denoised film, which keeps the sample counts and AOVs of `film`.
+    ///
+    /// # Arguments
+    /// * `film`    - The rendered film, whose AOVs of albedo and normal are used if rendered.
+    pub fn denoise(&self, film: &Film) -> Film {
+        let (width, height) = (film.width(), film.height());
+        let guides = film.aovs().map(|aovs| {
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| {
+                    let n = aovs.get(Aov::Normal, x, y);
+                    let a = aovs.get(Aov::Albedo, x, y);
+                    Guide {
+                        normal: Vec3::new(n[0], n[1], n[2]),
+                        albedo: Color::new(a[0], a[1], a[2]),
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+        let albedo = |i: usize| guides.as_ref().map_or(Color::WHITE, |g| g[i].albedo);
+
+        let mut colors = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .enumerate()
+            .map(|(i, (x, y))| demodulate(film.get(x, y), albedo(i)))
+            .collect::<Vec<_>>();
+        for i in 0..self.iterations {
+            let sigma_color = self.sigma_color / (1 << i) as f64;
+            colors = self.filter(&colors, guides.as_deref(), width, 1 << i, sigma_color);
+        }
+        let colors = colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| modulate(c, albedo(i)))
+            .collect();
+        film.with_colors(colors)
+    }
+
+    /// Returns the colors filtered by the kernel whose taps are `step` pixels apart.
+    fn filter(
+        &self,
+        colors: &[Color],
+        guides: Option<&[Guide]>,
+        width: u32,
+        step: i64,
+        sigma_color: f64,
+    ) -> Vec<Color> {
+        let (width, height) = (width as i64, (colors.len() as i64) / width as i64);
+        let mut output = vec![Color::BLACK; colors.len()];
+        output
+            .par_chunks_mut(width as usize)
+            .enumerate()
+            .for_each(|(y, row)| {
+                let y = y as i64;
+                for (x, dst) in row.iter_mut().enumerate() {
+                    let p = (y * width + x as i64) as usize;
+                    let mut sum = Color::BLACK;
+                    let mut weight_sum = 0.0;
+                    for (j, ky) in KERNEL.iter().enumerate() {
+                        let qy = (y + (j as i64 - 2) * step).clamp(0, height - 1);
+                        for (i, kx) in KERNEL.iter().enumerate() {
+                            let qx = (x as i64 + (i as i64 - 2) * step).clamp(0, width - 1);
+                            let q = (qy * width + qx) as usize;
+                            let mut w =
+                                kx * ky * gaussian(distance2(colors[p], colors[q]), sigma_color);
+                            if let Some(guides) = guides {
+                                let (gp, gq) = (guides[p], guides[q]);
+                                w *= gaussian(
+                                    (gp.normal - gq.normal).norm_squared(),
+                                    self.sigma_normal,
+                                );
+                                w *= gaussian(distance2(gp.albedo, gq.albedo), self.sigma_albedo);
+                            }
+                            sum += colors[q] * w;
+                            weight_sum += w;
+                        }
+                    }
+                    // The center tap has a positive weight, unless sigmas are zero.
+                    *dst = if weight_sum > 0.0 {
+                        sum / weight_sum
+                    } else {
+                        colors[p]
+                    };
+                }
+            });
+        output
+    }
+}
+
+/// Returns the weight of the squared distance `d2` with the tolerance `sigma`.
+fn gaussian(d2: f64, sigma: f64) -> f64 {
+    (-d2 / (sigma * sigma)).exp()
+}
+
+fn distance2(c1: Color, c2: Color) -> f64 {
+    let d = c1 - c2;
+    d.r * d.r + d.g * d.g + d.b * d.b
+}
+
+/// Returns the color divided by albedo, which is the illumination of the surface.
+fn demodulate(color: Color, albedo: Color) -> Color {
+    let div = |c: f64, a: f64| if a > MIN_ALBEDO { c / a } else { c };
+    Color::new(
+        div(color.r, albedo.r),
+        div(color.g, albedo.g),
+        div(color.b, albedo.b),
+    )
+}
+
+/// Returns the illumination multiplied by albedo, which is the inverse of `demodulate`.
+fn modulate(color: Color, albedo: Color) -> Color {
+    let mul = |c: f64, a: f64| if a > MIN_ALBEDO { c * a } else { c };
+    Color::new(
+        mul(color.r, albedo.r),
+        mul(color.g, albedo.g),
+        mul(color.b, albedo.b),
+    )
+}
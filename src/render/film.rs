@@ -0,0 +1,332 @@
+use image::RgbImage;
+
+use crate::color::Color;
+use crate::filter::Filter;
+use crate::render::aov::{AovPixel, AovSample};
+use crate::render::{AovBuffer, RenderStats};
+
+/// A weighted sum of samples splatted to a pixel.
+#[derive(Debug, Clone, Copy)]
+struct FilmPixel {
+    sum: Color,
+    weight: f64,
+}
+
+impl FilmPixel {
+    const ZERO: FilmPixel = FilmPixel {
+        sum: Color::BLACK,
+        weight: 0.0,
+    };
+}
+
+/// A buffer to accumulate filtered samples in floating point.
+///
+/// The pixel `(x, y)` covers the continuous range `[x, x + 1) x [y, y + 1)`,
+/// where `y` increases downward as the image row.
+///
+/// Besides samples of camera rays, light traced to the camera by bidirectional integrators is
+/// accumulated without the filter, which is averaged over the samples of the whole film.
+#[derive(Debug, Clone)]
+pub struct Film {
+    width: u32,
+    height: u32,
+    pixels: Vec<FilmPixel>,
+    counts: Vec<u32>,
+    light: Vec<Color>,
+    total_samples: u64,
+    stats: RenderStats,
+    aovs: Option<AovBuffer>,
+}
+
+impl Film {
+    /// Constructs empty `Film`.
+    ///
+    /// # Arguments
+    /// * `width`   - The image width.
+    /// * `height`  - The image height.
+    pub fn new(width: u32, height: u32) -> Self {
+        Film {
+            width,
+            height,
+            pixels: vec![FilmPixel::ZERO; (width * height) as usize],
+            counts: vec![0; (width * height) as usize],
+            light: vec![Color::BLACK; (width * height) as usize],
+            total_samples: 0,
+            stats: RenderStats::default(),
+            aovs: None,
+        }
+    }
+
+    /// Returns `Film` which also accumulates AOVs.
+    pub(crate) fn with_aovs(mut self) -> Self {
+        self.aovs = Some(AovBuffer::new(self.width, self.height));
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the color of the pixel, which is the weighted average of splatted samples
+    /// plus the light traced to the camera divided by the average number of samples per pixel.
+    ///
+    /// If the sum of weights is not positive, e.g. no sample is splatted, the samples are black.
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        let index = (y * self.width + x) as usize;
+        let pixel = &self.pixels[index];
+        let color = if pixel.weight > 0.0 {
+            pixel.sum / pixel.weight
+        } else {
+            Color::BLACK
+        };
+        if self.total_samples > 0 {
+            let pixels = self.width as f64 * self.height as f64;
+            color + self.light[index] * (pixels / self.total_samples as f64)
+        } else {
+            color
+        }
+    }
+
+    /// Returns AOVs, if they are rendered.
+    pub fn aovs(&self) -> Option<&AovBuffer> {
+        self.aovs.as_ref()
+    }
+
+    /// Returns the number of samples taken in the pixel.
+    pub fn sample_count(&self, x: u32, y: u32) -> u32 {
+        self.counts[(y * self.width + x) as usize]
+    }
+
+    /// Returns the total number of samples taken in the film.
+    pub fn total_samples(&self) -> u64 {
+        self.total_samples
+    }
+
+    /// Returns the statistics of rendering.
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+
+    /// Returns `Film` whose statistics are replaced by `stats`.
+    pub(crate) fn with_stats(mut self, stats: RenderStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Returns the heatmap of sample counts, which is normalized by the maximum count.
+    pub fn sample_heatmap(&self) -> RgbImage {
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        RgbImage::from_fn(self.width, self.height, |x, y| {
+            let t = self.sample_count(x, y) as f64 / max as f64;
+            image::Rgb(Color::heatmap(t).to_rgb())
+        })
+    }
+
+    /// Returns 8-bit image of the film.
+    pub fn to_image(&self) -> RgbImage {
+        RgbImage::from_fn(self.width, self.height, |x, y| {
+            image::Rgb(self.get(x, y).to_rgb())
+        })
+    }
+
+    /// Returns the weighted sum of colors, the sum of weights, the sample count and the light traced to the camera
+    /// of each pixel in row-major order.
+    pub(crate) fn raw_pixels(&self) -> impl Iterator<Item = (Color, f64, u32, Color)> + '_ {
+        self.pixels
+            .iter()
+            .zip(&self.counts)
+            .zip(&self.light)
+            .map(|((pixel, &count), &light)| (pixel.sum, pixel.weight, count, light))
+    }
+
+    /// Constructs `Film` from the values returned by `raw_pixels`.
+    ///
+    /// Returns `None`, if the number of pixels does not match the size.
+    pub(crate) fn from_raw_pixels(
+        width: u32,
+        height: u32,
+        raw: Vec<(Color, f64, u32, Color)>,
+    ) -> Option<Self> {
+        if raw.len() != width as usize * height as usize {
+            return None;
+        }
+        let (pixels, (counts, light)): (_, (Vec<_>, _)) = raw
+            .into_iter()
+            .map(|(sum, weight, count, light)| (FilmPixel { sum, weight }, (count, light)))
+            .unzip();
+        Some(Film {
+            width,
+            height,
+            pixels,
+            total_samples: counts.iter().map(|&c| c as u64).sum(),
+            counts,
+            light,
+            stats: RenderStats::default(),
+            aovs: None,
+        })
+    }
+
+    /// Returns `Film` whose pixels are replaced by `colors` in row-major order,
+    /// keeping the sample counts and AOVs, where the colors include the light traced to the camera.
+    pub(crate) fn with_colors(&self, colors: Vec<Color>) -> Film {
+        Film {
+            width: self.width,
+            height: self.height,
+            pixels: colors
+                .into_iter()
+                .map(|sum| FilmPixel { sum, weight: 1.0 })
+                .collect(),
+            counts: self.counts.clone(),
+            light: vec![Color::BLACK; self.light.len()],
+            total_samples: self.total_samples,
+            stats: self.stats,
+            aovs: self.aovs.clone(),
+        }
+    }
+
+    /// Adds the samples of another film of the same size.
+    pub(crate) fn accumulate(&mut self, other: &Film) {
+        for (dst, src) in self.pixels.iter_mut().zip(&other.pixels) {
+            dst.sum += src.sum;
+            dst.weight += src.weight;
+        }
+        for (dst, src) in self.counts.iter_mut().zip(&other.counts) {
+            *dst += src;
+        }
+        for (dst, src) in self.light.iter_mut().zip(&other.light) {
+            *dst += *src;
+        }
+        self.total_samples += other.total_samples;
+        self.stats.merge(&other.stats);
+        if let (Some(dst), Some(src)) = (&mut self.aovs, &other.aovs) {
+            for (dst, src) in dst.pixels.iter_mut().zip(&src.pixels) {
+                dst.merge(src);
+            }
+        }
+    }
+
+    /// Returns `FilmTile` to splat samples of the pixels in `[x0, x1) x [y0, y1)`.
+    ///
+    /// The tile is extended by the filter radius to receive samples splatted outside of the pixels.
+    pub(crate) fn tile<'f>(
+        &self,
+        (x0, y0): (u32, u32),
+        (x1, y1): (u32, u32),
+        filter: &'f dyn Filter,
+    ) -> FilmTile<'f> {
+        let r = filter.radius().ceil() as u32;
+        let (x0, y0) = (x0.saturating_sub(r), y0.saturating_sub(r));
+        let (x1, y1) = ((x1 + r).min(self.width), (y1 + r).min(self.height));
+        FilmTile {
+            x0,
+            y0,
+            x1,
+            y1,
+            filter,
+            pixels: vec![FilmPixel::ZERO; ((x1 - x0) * (y1 - y0)) as usize],
+            counts: vec![0; ((x1 - x0) * (y1 - y0)) as usize],
+            light: Vec::new(),
+            stats: RenderStats::default(),
+            aovs: self
+                .aovs
+                .as_ref()
+                .map(|_| vec![AovPixel::ZERO; ((x1 - x0) * (y1 - y0)) as usize]),
+        }
+    }
+
+    /// Adds the samples splatted to the tile.
+    pub(crate) fn merge(&mut self, tile: FilmTile) {
+        let width = tile.x1 - tile.x0;
+        for (i, (src, count)) in tile.pixels.iter().zip(tile.counts).enumerate() {
+            let (x, y) = (tile.x0 + i as u32 % width, tile.y0 + i as u32 / width);
+            let index = (y * self.width + x) as usize;
+            let dst = &mut self.pixels[index];
+            dst.sum += src.sum;
+            dst.weight += src.weight;
+            self.counts[index] += count;
+            self.total_samples += count as u64;
+        }
+        self.stats.merge(&tile.stats);
+        for (fx, fy, color) in tile.light {
+            let x = (fx.max(0.0) as u32).min(self.width - 1);
+            let y = (fy.max(0.0) as u32).min(self.height - 1);
+            self.light[(y * self.width + x) as usize] += color;
+        }
+        if let (Some(dst), Some(src)) = (&mut self.aovs, &tile.aovs) {
+            for (i, src) in src.iter().enumerate() {
+                let (x, y) = (tile.x0 + i as u32 % width, tile.y0 + i as u32 / width);
+                dst.pixels[(y * self.width + x) as usize].merge(src);
+            }
+        }
+    }
+}
+
+/// A part of `Film` where worker threads splat samples independently.
+pub(crate) struct FilmTile<'f> {
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+    filter: &'f dyn Filter,
+    pixels: Vec<FilmPixel>,
+    counts: Vec<u32>,
+    // The light traced to the camera at the continuous positions anywhere on the film.
+    light: Vec<(f64, f64, Color)>,
+    stats: RenderStats,
+    aovs: Option<Vec<AovPixel>>,
+}
+
+impl FilmTile<'_> {
+    /// Adds the number of samples taken in the pixel (`x`, `y`).
+    pub(crate) fn add_count(&mut self, x: u32, y: u32, count: u32) {
+        let index = (y - self.y0) * (self.x1 - self.x0) + x - self.x0;
+        self.counts[index as usize] += count;
+    }
+
+    /// Returns the statistics of rendering the tile to update.
+    pub(crate) fn stats_mut(&mut self) -> &mut RenderStats {
+        &mut self.stats
+    }
+
+    /// Returns `true`, if the tile accumulates AOVs.
+    pub(crate) fn has_aovs(&self) -> bool {
+        self.aovs.is_some()
+    }
+
+    /// Adds the AOV sample of the camera ray in the pixel (`x`, `y`), or the miss if `None`.
+    pub(crate) fn add_aov(&mut self, x: u32, y: u32, sample: Option<AovSample>) {
+        let index = (y - self.y0) * (self.x1 - self.x0) + x - self.x0;
+        if let Some(aovs) = &mut self.aovs {
+            aovs[index as usize].add(sample);
+        }
+    }
+
+    /// Adds the light traced to the camera at the continuous position (`fx`, `fy`), which may be out of the tile.
+    pub(crate) fn add_light(&mut self, fx: f64, fy: f64, color: Color) {
+        self.light.push((fx, fy, color));
+    }
+
+    /// Splats the sample at the continuous position (`fx`, `fy`) to the pixels within the filter radius.
+    pub(crate) fn add_sample(&mut self, fx: f64, fy: f64, color: Color) {
+        let r = self.filter.radius();
+        let xs = ((fx - 0.5 - r).ceil().max(self.x0 as f64) as u32)
+            ..=((fx - 0.5 + r).floor().min(self.x1 as f64 - 1.0) as u32);
+        let ys = ((fy - 0.5 - r).ceil().max(self.y0 as f64) as u32)
+            ..=((fy - 0.5 + r).floor().min(self.y1 as f64 - 1.0) as u32);
+        let width = self.x1 - self.x0;
+        for y in ys {
+            for x in xs.clone() {
+                let weight = self.filter.eval(x as f64 + 0.5 - fx, y as f64 + 0.5 - fy);
+                if weight != 0.0 {
+                    let pixel = &mut self.pixels[((y - self.y0) * width + x - self.x0) as usize];
+                    pixel.sum += color * weight;
+                    pixel.weight += weight;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::color::Color;
+use crate::interval::Interval;
+use crate::material::bsdf::uniform_sphere;
+use crate::material::facing_normal;
+use crate::ray::Ray;
+use crate::render::{Integrator, SampleContext, Scene, T_MIN};
+use crate::sampler::Dimension;
+use crate::shape::{HitInfo, Shape};
+
+/// The default radius of ambient occlusion.
+const DEFAULT_AO_RADIUS: f64 = 1.0;
+
+/// Represents the type of integrator.
+///
+/// # Variants
+/// * `Path`            - Forward path tracing with next-event estimation.
+/// * `Bidirectional`   - Bidirectional path tracing, which connects camera and light subpaths by all strategies.
+/// * `PhotonMapping`   - Photon mapping, which estimates the light reflected by non-specular surfaces from photons.
+/// * `ProgressivePhotonMapping`    - Photon mapping which emits photons for each sample per pixel with the radius shrinking for all pixels.
+/// * `Normal`          - The normal at the first hit.
+/// * `Albedo`          - The albedo of the material at the first hit.
+/// * `Depth`           - The linear depth of the first hit along the camera axis.
+/// * `AmbientOcclusion`    - The ratio of directions not occluded within the radius around the first hit.
+/// * `Uv`              - The texture coordinates at the first hit.
+/// * `BvhCost`         - The heatmap of bounding boxes and primitives tested to find the first hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegratorType {
+    #[default]
+    Path,
+    Bidirectional,
+    PhotonMapping,
+    ProgressivePhotonMapping,
+    Normal,
+    Albedo,
+    Depth,
+    AmbientOcclusion,
+    Uv,
+    BvhCost,
+}
+
+impl IntegratorType {
+    /// Constructs the integrator.
+    ///
+    /// # Arguments
+    /// * `ao_radius`   - The radius of ambient occlusion, or `1.0` if `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::render::IntegratorType;
+    ///
+    /// let integrator = IntegratorType::AmbientOcclusion.build(Some(0.5));
+    /// assert!(integrator.photon_emission().is_none());
+    /// ```
+    pub fn build(&self, ao_radius: Option<f64>) -> Box<dyn Integrator> {
+        match self {
+            IntegratorType::Path => Box::new(PathIntegrator),
+            IntegratorType::Bidirectional => Box::new(BidirectionalIntegrator),
+            IntegratorType::PhotonMapping => Box::new(PhotonMappingIntegrator::new(false)),
+            IntegratorType::ProgressivePhotonMapping => {
+                Box::new(PhotonMappingIntegrator::new(true))
+            }
+            IntegratorType::Normal => Box::new(NormalIntegrator),
+            IntegratorType::Albedo => Box::new(AlbedoIntegrator),
+            IntegratorType::Depth => Box::new(DepthIntegrator),
+            IntegratorType::AmbientOcclusion => Box::new(AmbientOcclusionIntegrator::new(
+                ao_radius.unwrap_or(DEFAULT_AO_RADIUS),
+            )),
+            IntegratorType::Uv => Box::new(UvIntegrator),
+            IntegratorType::BvhCost => Box::new(BvhCostIntegrator),
+        }
+    }
+}
+
+impl FromStr for IntegratorType {
+    type Err = String;
+
+    /// Parses the integrator type from `path`, `bidirectional`, `photon_mapping`, `progressive_photon_mapping`,
+    /// `normal`, `albedo`, `depth`, `ambient_occlusion`, `uv` or `bvh_cost`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "path" => Ok(IntegratorType::Path),
+            "bidirectional" => Ok(IntegratorType::Bidirectional),
+            "photon_mapping" => Ok(IntegratorType::PhotonMapping),
+            "progressive_photon_mapping" => Ok(IntegratorType::ProgressivePhotonMapping),
+            "normal" => Ok(IntegratorType::Normal),
+            "albedo" => Ok(IntegratorType::Albedo),
+            "depth" => Ok(IntegratorType::Depth),
+            "ambient_occlusion" => Ok(IntegratorType::AmbientOcclusion),
+            "uv" => Ok(IntegratorType::Uv),
+            "bvh_cost" => Ok(IntegratorType::BvhCost),
+            _ => Err(format!(
+                "unknown integrator `{}`, expected one of path, bidirectional, photon_mapping, progressive_photon_mapping, normal, albedo, depth, ambient_occlusion, uv, bvh_cost",
+                s
+            )),
+        }
+    }
+}
+
+/// Represents when photon maps are emitted for the integrator.
+///
+/// # Variants
+/// * `Pass`    - A photon map with the fixed radius for each pass.
+/// * `Sample`  - A photon map for each sample per pixel in Anti-Aliasing mode, whose radius shrinks progressively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhotonEmission {
+    Pass,
+    Sample,
+}
+
+/// Forward path tracing with next-event estimation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PathIntegrator;
+
+impl Integrator for PathIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, sample: &mut SampleContext) -> Color {
+        scene.ray_color(&ray, scene.max_depth, Color::WHITE, None, sample)
+    }
+}
+
+/// Bidirectional path tracing, which adds the light traced to the camera to the tile.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BidirectionalIntegrator;
+
+impl Integrator for BidirectionalIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, sample: &mut SampleContext) -> Color {
+        scene.trace_bidirectional(ray, sample)
+    }
+}
+
+/// Photon mapping, which gathers photons from the photon map of the pass.
+///
+/// # Arguments
+/// * `progressive` - Whether photons are emitted for each sample per pixel with the shrinking radius.
+#[derive(Debug, Clone, Copy)]
+pub struct PhotonMappingIntegrator {
+    progressive: bool,
+}
+
+impl PhotonMappingIntegrator {
+    /// Constructs `PhotonMappingIntegrator`.
+    ///
+    /// # Arguments
+    /// * `progressive` - Whether photons are emitted for each sample per pixel with the shrinking radius.
+    pub fn new(progressive: bool) -> Self {
+        PhotonMappingIntegrator { progressive }
+    }
+}
+
+impl Integrator for PhotonMappingIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, sample: &mut SampleContext) -> Color {
+        match sample.photons {
+            Some(photons) => scene.trace_photon_mapping(ray, photons, sample),
+            None => Color::BLACK,
+        }
+    }
+
+    fn photon_emission(&self) -> Option<PhotonEmission> {
+        if self.progressive {
+            Some(PhotonEmission::Sample)
+        } else {
+            Some(PhotonEmission::Pass)
+        }
+    }
+}
+
+/// Returns the first hit of the camera ray.
+fn first_hit(scene: &Scene, ray: &Ray) -> Option<HitInfo> {
+    scene
+        .world
+        .hit(ray, Interval::from_val(T_MIN, f64::INFINITY))
+}
+
+/// Shows the normal at the first hit mapped from `[-1.0, 1.0]` to `[0.0, 1.0]`, or the background.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalIntegrator;
+
+impl Integrator for NormalIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        match first_hit(scene, &ray) {
+            Some(info) => 0.5 * (Color::new(*info.n.x(), *info.n.y(), *info.n.z()) + Color::WHITE),
+            None => scene.background(ray.direction),
+        }
+    }
+}
+
+/// Shows the albedo of the material at the first hit, or the background.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlbedoIntegrator;
+
+impl Integrator for AlbedoIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        match first_hit(scene, &ray) {
+            Some(info) => info.m.albedo(&info),
+            None => scene.background(ray.direction),
+        }
+    }
+}
+
+/// Shows the linear depth `d` of the first hit along the camera axis as `1 / (1 + d)`,
+/// which is white at the camera and black at the background.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DepthIntegrator;
+
+impl Integrator for DepthIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        match first_hit(scene, &ray) {
+            Some(info) => {
+                let depth = (info.p - scene.camera.origin).dot(scene.camera.forward());
+                let t = 1.0 / (1.0 + depth.max(0.0));
+                Color::new(t, t, t)
+            }
+            None => Color::BLACK,
+        }
+    }
+}
+
+/// Shows ambient occlusion, which is white if the direction sampled around the first hit
+/// in proportion to the cosine is not occluded within the radius.
+///
+/// # Arguments
+/// * `radius`  - The distance where occluders are searched.
+#[derive(Debug, Clone, Copy)]
+pub struct AmbientOcclusionIntegrator {
+    radius: f64,
+}
+
+impl AmbientOcclusionIntegrator {
+    /// Constructs `AmbientOcclusionIntegrator`.
+    ///
+    /// # Arguments
+    /// * `radius`  - The distance where occluders are searched.
+    pub fn new(radius: f64) -> Self {
+        AmbientOcclusionIntegrator { radius }
+    }
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, sample: &mut SampleContext) -> Color {
+        let Some(info) = first_hit(scene, &ray) else {
+            return Color::WHITE;
+        };
+        // The hemisphere on the side of the camera.
+        let n = if info.n.dot(ray.direction) > 0.0 {
+            -info.n
+        } else {
+            info.n
+        };
+        let mut direction = n + uniform_sphere(sample.sample_2d(Dimension::Bsdf(0)));
+        if direction.is_close(0.0) {
+            direction = n;
+        }
+        let occluder = scene.world.hit(
+            &Ray::new(info.p, direction.as_unit()),
+            Interval::from_val(T_MIN, self.radius),
+        );
+        match occluder {
+            Some(_) => Color::BLACK,
+            None => Color::WHITE,
+        }
+    }
+}
+
+/// Shows the texture coordinates at the first hit as red and green, or black at the background.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UvIntegrator;
+
+impl Integrator for UvIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        match first_hit(scene, &ray) {
+            Some(info) => Color::new(info.u, info.v, 0.0),
+            None => Color::BLACK,
+        }
+    }
+}
+
+/// Shows the heatmap of bounding boxes and primitives tested to find the first hit in the log scale,
+/// which is red at `MAX_COST` tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BvhCostIntegrator;
+
+impl BvhCostIntegrator {
+    /// The number of tests at the top of the heatmap.
+    pub const MAX_COST: u32 = 1024;
+}
+
+impl Integrator for BvhCostIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        let cost = scene
+            .world
+            .traversal_cost(&ray, Interval::from_val(T_MIN, f64::INFINITY));
+        let t = (cost as f64).ln_1p() / (Self::MAX_COST as f64).ln_1p();
+        Color::heatmap(t)
+    }
+}
+
+/// A classic Whitted-style ray tracer for fast previews.
+///
+/// Materials of only delta lobes, e.g. `Dilectric` and `Metal` without fuzz, are traced recursively
+/// in the perfect mirror or refraction direction,
+/// and the other materials are shaded by the normalized Blinn-Phong model driven by their albedo
+/// with hard shadows from point, spot and directional lights, and the background in the normal direction as ambient light.
+///
+/// # Arguments
+/// * `shininess`   - The exponent of the highlight.
+/// * `specular`    - The reflectance of the highlight.
+/// * `ambient`     - The ratio of the background lighting surfaces.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+///
+/// use raytrs::camera::Camera;
+/// use raytrs::color::Color;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::light::PointLight;
+/// use raytrs::material::{ColorTexture, Lambertian, MetallicRoughness};
+/// use raytrs::render::{RenderMode, RenderOptions, Scene};
+/// use raytrs::shape::Sphere;
+///
+/// let camera = || {
+///     Camera::from_lookat(
+///         Vec3::new(0.0, 0.0, 0.0),
+///         Vec3::new(0.0, 0.0, -1.0),
+///         Vec3::new(0.0, 1.0, 0.0),
+///         90.0,
+///         8,
+///         8,
+///     )
+/// };
+/// let mut scene = Scene::new(camera());
+/// scene.push(Box::new(Sphere::new(
+///     Vec3::new(0.0, 0.0, -2.0),
+///     1.0,
+///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+/// )));
+/// let ambient = scene.render_film(&RenderOptions::new(RenderMode::WHITTED));
+///
+/// // The point light above the sphere lights its top.
+/// scene.add_light(Box::new(PointLight::new(
+///     Vec3::new(0.0, 3.0, -2.0),
+///     Color::new(10.0, 10.0, 10.0),
+/// )));
+/// let film = scene.render_film(&RenderOptions::new(RenderMode::WHITTED));
+/// assert!(film.get(4, 2).luminance() > ambient.get(4, 2).luminance());
+///
+/// // A smooth metal reflects the background behind the camera like a mirror.
+/// let mut mirror = Scene::new(camera());
+/// mirror.push(Box::new(Sphere::new(
+///     Vec3::new(0.0, 0.0, -2.0),
+///     1.0,
+///     Arc::new(MetallicRoughness::new(Box::new(ColorTexture::new(Color::WHITE)), 1.0, 0.0)),
+/// )));
+/// let film = mirror.render_film(&RenderOptions::new(RenderMode::WHITTED));
+/// assert!(film.get(4, 4).luminance() > 0.7);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WhittedIntegrator {
+    shininess: f64,
+    specular: f64,
+    ambient: f64,
+}
+
+impl Default for WhittedIntegrator {
+    fn default() -> Self {
+        WhittedIntegrator::new(32.0, 0.04, 0.5)
+    }
+}
+
+impl WhittedIntegrator {
+    /// Constructs `WhittedIntegrator`.
+    ///
+    /// # Arguments
+    /// * `shininess`   - The exponent of the highlight.
+    /// * `specular`    - The reflectance of the highlight.
+    /// * `ambient`     - The ratio of the background lighting surfaces.
+    pub fn new(shininess: f64, specular: f64, ambient: f64) -> Self {
+        WhittedIntegrator {
+            shininess,
+            specular,
+            ambient,
+        }
+    }
+
+    /// Returns the color of the ray reflected or refracted at most `depth` times.
+    fn trace(&self, scene: &Scene, ray: &Ray, depth: u32) -> Color {
+        if depth == 0 {
+            return Color::BLACK;
+        }
+        let Some(info) = first_hit(scene, ray) else {
+            return scene.background(ray.direction);
+        };
+        let wo = -ray.direction.as_unit();
+        let emitted = info.m.emitted(info.u, info.v, info.p);
+        if info.m.is_delta(&info) {
+            // Delta lobes are chosen and sampled without the uniform numbers.
+            let Some(sample) = info.m.sample(wo, &info, [0.0; 3]) else {
+                return emitted;
+            };
+            let scattered = Ray::new(info.p, sample.wi);
+            return emitted + sample.weight() * self.trace(scene, &scattered, depth - 1);
+        }
+
+        let n = facing_normal(info.n, wo);
+        let albedo = info.m.albedo(&info);
+        // The normalization keeps the energy of the highlight as the shininess changes.
+        let highlight = self.specular * (self.shininess + 8.0) / (8.0 * PI);
+        let mut color = emitted + albedo * scene.background(n) * self.ambient;
+        for light in &scene.lights {
+            // Lights are sampled at the center for hard shadows.
+            let Some(sample) = light.sample(info.p, (0.5, 0.5)) else {
+                continue;
+            };
+            let cos = n.dot(sample.wi);
+            if cos <= 0.0 {
+                continue;
+            }
+            let shadow = Ray::new(info.p, sample.wi);
+            let interval = Interval::from_val(T_MIN, sample.distance - T_MIN);
+            if scene.world.hit(&shadow, interval).is_some() {
+                continue;
+            }
+            let h = (wo + sample.wi).as_unit();
+            let phong = highlight * n.dot(h).max(0.0).powf(self.shininess);
+            color += (albedo / PI + Color::new(phong, phong, phong)) * sample.li * cos;
+        }
+        color
+    }
+}
+
+impl Integrator for WhittedIntegrator {
+    fn li(&self, scene: &Scene, ray: Ray, _sample: &mut SampleContext) -> Color {
+        self.trace(scene, &ray, scene.max_depth)
+    }
+}
@@ -0,0 +1,309 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::render::integrator::PathIntegrator;
+use crate::render::{Integrator, RenderMode, Tile};
+
+/// A snapshot of rendering progress.
+///
+/// # Arguments
+/// * `completed`   - The number of completed tiles.
+/// * `total`       - The total number of tiles.
+/// * `elapsed`     - The elapsed time since the rendering started.
+/// * `tile`        - The tile which has just been completed.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    pub completed: u32,
+    pub total: u32,
+    pub elapsed: Duration,
+    pub tile: Tile,
+}
+
+impl Progress {
+    /// Returns the completed ratio in `[0.0, 1.0]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use raytrs::render::{Progress, Tile};
+    ///
+    /// let progress = Progress {
+    ///     completed: 25,
+    ///     total: 100,
+    ///     elapsed: Duration::from_secs(1),
+    ///     tile: Tile { x0: 0, y0: 0, x1: 32, y1: 32 },
+    /// };
+    /// assert_eq!(progress.ratio(), 0.25);
+    /// ```
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f64 / self.total as f64
+        }
+    }
+
+    /// Returns the estimated remaining time, which is extrapolated from the elapsed time.
+    ///
+    /// Returns `None` until any tile is completed.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use raytrs::render::{Progress, Tile};
+    ///
+    /// let progress = Progress {
+    ///     completed: 25,
+    ///     total: 100,
+    ///     elapsed: Duration::from_secs(1),
+    ///     tile: Tile { x0: 0, y0: 0, x1: 32, y1: 32 },
+    /// };
+    /// assert_eq!(progress.eta(), Some(Duration::from_secs(3)));
+    /// ```
+    pub fn eta(&self) -> Option<Duration> {
+        if self.completed == 0 {
+            return None;
+        }
+        let remaining = self.total.saturating_sub(self.completed);
+        Some(
+            self.elapsed
+                .mul_f64(remaining as f64 / self.completed as f64),
+        )
+    }
+}
+
+/// A token to cancel rendering cooperatively.
+///
+/// Clones share the same state, so the token can be cancelled from another thread.
+///
+/// # Examples
+/// ```
+/// use raytrs::render::CancelToken;
+///
+/// let token = CancelToken::new();
+/// let other = token.clone();
+/// other.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// Constructs `CancelToken` which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests to cancel rendering.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true`, if cancellation is requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+type ProgressCallback<'a> = Box<dyn Fn(Progress) + Send + Sync + 'a>;
+
+/// Options of rendering.
+///
+/// # Arguments
+/// * `mode`        - The rendering mode.
+/// * `progress`    - The optional callback called from worker threads whenever a tile is completed.
+/// * `cancel`      - The optional token checked by workers before rendering each tile.
+/// * `deadline`    - The optional time after which rendering is cancelled.
+/// * `aovs`        - Whether to render AOVs of the first hits into `Film`.
+/// * `integrator`  - The integrator to estimate the color of camera rays, which is the path tracer by default.
+///
+/// # Examples
+/// ```
+/// use raytrs::camera::Camera;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::render::{CancelToken, RenderMode, RenderOptions, Renderer, Scene, TileOrder};
+///
+/// let mut scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     16,
+///     8,
+/// ));
+/// scene.set_tiles(4, TileOrder::Scanline);
+///
+/// let token = CancelToken::new();
+/// let options = RenderOptions::new(RenderMode::BASIC)
+///     .with_progress(|progress| {
+///         if progress.completed >= 4 {
+///             token.cancel();
+///         }
+///     })
+///     .with_cancel(token.clone());
+/// let img = scene.render_with(&options);
+/// assert!(token.is_cancelled());
+/// assert_eq!(img.dimensions(), (16, 8));
+/// ```
+pub struct RenderOptions<'a> {
+    mode: RenderMode,
+    progress: Option<ProgressCallback<'a>>,
+    cancel: Option<CancelToken>,
+    deadline: Option<Instant>,
+    aovs: bool,
+    integrator: &'a dyn Integrator,
+}
+
+impl<'a> RenderOptions<'a> {
+    /// Constructs `RenderOptions` without progress callback and cancellation.
+    ///
+    /// # Arguments
+    /// * `mode`    - The rendering mode.
+    pub fn new(mode: RenderMode) -> Self {
+        RenderOptions {
+            mode,
+            progress: None,
+            cancel: None,
+            deadline: None,
+            aovs: false,
+            integrator: &PathIntegrator,
+        }
+    }
+
+    /// Sets the callback to report progress.
+    pub fn with_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(Progress) + Send + Sync + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Sets the token to cancel rendering.
+    pub fn with_cancel(mut self, token: CancelToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Sets the time after which rendering is cancelled.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Enables to render AOVs, which are available from `Film::aovs`.
+    pub fn with_aovs(mut self) -> Self {
+        self.aovs = true;
+        self
+    }
+
+    /// Sets the integrator to estimate the color of camera rays,
+    /// so the same scene can be rendered by different integrators.
+    ///
+    /// `RenderMode::WHITTED` ignores the integrator and traces by `WhittedIntegrator`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::camera::Camera;
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, DiffuseLight, Lambertian};
+    /// use raytrs::render::{IntegratorType, RenderMode, RenderOptions, Scene};
+    /// use raytrs::shape::Sphere;
+    ///
+    /// let mut scene = Scene::new(Camera::from_lookat(
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 0.0, -1.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     90.0,
+    ///     8,
+    ///     8,
+    /// ));
+    /// scene.push(Box::new(Sphere::new(
+    ///     Vec3::new(0.0, 0.0, -2.0),
+    ///     1.0,
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
+    /// )));
+    /// scene.push(Box::new(Sphere::new(
+    ///     Vec3::new(0.0, 2.0, -1.0),
+    ///     0.5,
+    ///     Arc::new(DiffuseLight::new(Box::new(ColorTexture::new(Color::new(4.0, 4.0, 4.0))))),
+    /// )));
+    ///
+    /// let bidirectional = IntegratorType::Bidirectional.build(None);
+    /// let options = RenderOptions::new(RenderMode::AA(4)).with_integrator(bidirectional.as_ref());
+    /// let film = scene.render_film(&options);
+    /// assert!(film.get(4, 2).luminance() > 0.0);
+    /// ```
+    pub fn with_integrator(mut self, integrator: &'a dyn Integrator) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Returns the rendering mode.
+    pub fn mode(&self) -> &RenderMode {
+        &self.mode
+    }
+
+    /// Returns the integrator to estimate the color of camera rays.
+    pub fn integrator(&self) -> &'a dyn Integrator {
+        self.integrator
+    }
+
+    /// Returns `true`, if AOVs are rendered.
+    pub fn aovs(&self) -> bool {
+        self.aovs
+    }
+
+    /// Returns `true`, if cancellation is requested or the deadline has passed.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// A counter of completed tiles shared by worker threads.
+pub(crate) struct ProgressTracker<'o, 'a> {
+    options: &'o RenderOptions<'a>,
+    completed: AtomicU32,
+    total: u32,
+    start: Instant,
+}
+
+impl<'o, 'a> ProgressTracker<'o, 'a> {
+    pub(crate) fn new(options: &'o RenderOptions<'a>, total: u32) -> Self {
+        ProgressTracker {
+            options,
+            completed: AtomicU32::new(0),
+            total,
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns `true`, if the options request cancellation.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.options.is_cancelled()
+    }
+
+    /// Marks the tile completed, and reports progress to the callback.
+    pub(crate) fn complete(&self, tile: Tile) {
+        let completed = self.completed.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(callback) = &self.options.progress {
+            callback(Progress {
+                completed,
+                total: self.total,
+                elapsed: self.start.elapsed(),
+                tile,
+            });
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use std::f64::consts::PI;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Deserializer};
+
+use crate::color::Color;
+use crate::geometry::vec3::orthonormal_basis;
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::material::bsdf::uniform_sphere;
+use crate::ray::Ray;
+use crate::render::{Background, SampleContext, Scene, T_MIN};
+use crate::sampler::{Dimension, PixelSample};
+use crate::shape::{Aabb, HitInfo, Shape};
+
+/// The column of the pixel whose samples are drawn for photons, which is distinct from camera samples.
+const PHOTON_PIXEL: u32 = u32::MAX;
+
+/// Parameters of photon mapping.
+///
+/// Photons are emitted from emissive primitives and the background in proportion to their power.
+/// Photons from the background enter the scene through the disk covering `bounds`,
+/// which should be the region of interest if the scene has a huge object such as the ground sphere.
+///
+/// # Arguments
+/// * `photons` - The number of photons emitted in each pass.
+/// * `radius`  - The radius to gather photons, which is the initial radius of progressive photon mapping.
+/// * `alpha`   - The ratio of photons kept in each pass of progressive photon mapping in `(0, 1)`.
+/// * `bounds`  - The optional bounds lit by the background, which are the bounds of the scene by default.
+///
+/// # Examples
+/// ```
+/// use raytrs::render::PhotonSettings;
+///
+/// let settings = PhotonSettings::default();
+/// assert_eq!(settings.radius(0), settings.radius);
+/// assert!(settings.radius(10) < settings.radius(1));
+/// ```
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PhotonSettings {
+    pub photons: usize,
+    pub radius: f64,
+    pub alpha: f64,
+    #[serde(deserialize_with = "deserialize_bounds")]
+    pub bounds: Option<Aabb>,
+}
+
+impl Default for PhotonSettings {
+    fn default() -> Self {
+        PhotonSettings {
+            photons: 100_000,
+            radius: 0.1,
+            alpha: 0.7,
+            bounds: None,
+        }
+    }
+}
+
+impl PhotonSettings {
+    /// Returns the radius to gather photons in the `pass`-th pass of progressive photon mapping,
+    /// whose square shrinks by `(i + alpha) / (i + 1)` after the `i`-th pass counted from `1`.
+    ///
+    /// The radius is shared by all pixels as in the probabilistic formulation by Knaus and Zwicker,
+    /// instead of the radius and photon count kept per hit point in the original progressive photon mapping,
+    /// so the bias vanishes more slowly in pixels with dense photons.
+    pub fn radius(&self, pass: usize) -> f64 {
+        let r2 = (1..=pass).fold(self.radius * self.radius, |r2, i| {
+            r2 * (i as f64 + self.alpha) / (i as f64 + 1.0)
+        });
+        r2.sqrt()
+    }
+}
+
+/// Deserializes the bounds from the minimum and the maximum corners.
+fn deserialize_bounds<'de, D>(deserializer: D) -> Result<Option<Aabb>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let corners = Option::<[[f64; 3]; 2]>::deserialize(deserializer)?;
+    Ok(corners.map(|[a, b]| Aabb::new(Vec3::new(a[0], a[1], a[2]), Vec3::new(b[0], b[1], b[2]))))
+}
+
+/// A photon stored where it hits a surface.
+///
+/// # Arguments
+/// * `p`       - The position.
+/// * `wi`      - The unit direction toward where the photon comes from.
+/// * `power`   - The power carried by the photon.
+/// * `axis`    - The axis to split the children in the kd-tree.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Photon {
+    p: Vec3,
+    wi: Vec3,
+    power: Color,
+    axis: u8,
+}
+
+/// A balanced kd-tree of photons to find photons around a point.
+///
+/// The tree is implicit in the order of photons, where the median of each range is the node
+/// and the lower and upper halves are the children.
+pub(crate) struct PhotonMap {
+    photons: Vec<Photon>,
+    radius: f64,
+}
+
+impl PhotonMap {
+    /// Constructs `PhotonMap` to gather photons within `radius`.
+    pub(crate) fn new(mut photons: Vec<Photon>, radius: f64) -> Self {
+        build(&mut photons);
+        PhotonMap { photons, radius }
+    }
+
+    /// Returns the radiance reflected toward `wo` at the hit point, which is estimated from the density of photons.
+    pub(crate) fn estimate(&self, wo: Vec3, info: &HitInfo) -> Color {
+        let mut sum = Color::BLACK;
+        self.for_each_within(
+            &self.photons,
+            info.p,
+            self.radius * self.radius,
+            &mut |photon| {
+                // The material returns BSDF times the cosine, which is divided out for the flux.
+                let cos = info.n.dot(photon.wi).abs();
+                if cos > 1e-6 {
+                    sum += info.m.eval(wo, photon.wi, info) * photon.power / cos;
+                }
+            },
+        );
+        sum / (PI * self.radius * self.radius)
+    }
+
+    /// Calls `f` with each photon within the squared distance `r2` from `p` in the subtree of `photons`.
+    fn for_each_within<F>(&self, photons: &[Photon], p: Vec3, r2: f64, f: &mut F)
+    where
+        F: FnMut(&Photon),
+    {
+        if photons.is_empty() {
+            return;
+        }
+        let mid = photons.len() / 2;
+        let node = &photons[mid];
+        let axis = node.axis as usize;
+        let d = p[axis] - node.p[axis];
+        let (near, far) = if d <= 0.0 {
+            (&photons[..mid], &photons[mid + 1..])
+        } else {
+            (&photons[mid + 1..], &photons[..mid])
+        };
+        self.for_each_within(near, p, r2, f);
+        if (node.p - p).norm_squared() <= r2 {
+            f(node);
+        }
+        if d * d < r2 {
+            self.for_each_within(far, p, r2, f);
+        }
+    }
+}
+
+/// Reorders photons into the balanced kd-tree split at the median on the longest axis.
+fn build(photons: &mut [Photon]) {
+    if photons.len() <= 1 {
+        return;
+    }
+    let bounds = photons
+        .iter()
+        .fold(Aabb::EMPTY, |bounds, photon| bounds.grow(photon.p));
+    let axis = bounds.longest_axis();
+    let mid = photons.len() / 2;
+    photons.select_nth_unstable_by(mid, |a, b| a.p[axis].total_cmp(&b.p[axis]));
+    photons[mid].axis = axis as u8;
+    let (lower, upper) = photons.split_at_mut(mid);
+    build(lower);
+    build(&mut upper[1..]);
+}
+
+impl Scene {
+    /// Returns the photon map of the `pass`-th pass with the radius to gather photons.
+    ///
+    /// Photons are the samples of the pixel reserved for the pass, whose sample indices are the photons,
+    /// so the photons are stratified by the sampler of the scene and the photon map does not depend on the number of threads.
+    pub(crate) fn photon_map(&self, pass: usize, radius: f64) -> PhotonMap {
+        let settings = &self.photon_settings;
+        let bounds = settings.bounds.unwrap_or_else(|| self.world.bounding_box());
+        let center = bounds.centroid();
+        let radius_bounds = (bounds.max - bounds.min).norm() * 0.5;
+        let emitter_power = self.emitters.iter().map(|e| e.power()).sum::<f64>();
+        // The flux of the background through the disk, which is comparable to the power of emitters.
+        let background_power =
+            4.0 * PI * self.background_luminance() * PI * radius_bounds * radius_bounds;
+        let total = emitter_power + background_power;
+        if total <= 0.0 || settings.photons == 0 {
+            return PhotonMap::new(Vec::new(), radius);
+        }
+        let p_emitter = emitter_power / total;
+        let scale = 1.0 / settings.photons as f64;
+        let sampler = self.sampler.build(settings.photons, self.seed);
+
+        let photons = (0..settings.photons)
+            .into_par_iter()
+            .flat_map_iter(|i| {
+                let sample =
+                    PixelSample::new(sampler.as_ref(), (PHOTON_PIXEL, pass as u32), i as u32);
+                // The emitter is chosen by the rest of the number choosing emitters or the background.
+                let (u, _) = sample.get(Dimension::Selection(0));
+                let emitted = if u < p_emitter {
+                    self.sample_emitter_ray(&sample, 0, u / p_emitter)
+                        .map(|(ray, beta)| (ray, beta / p_emitter))
+                } else {
+                    self.sample_background_ray(center, radius_bounds, &sample)
+                        .map(|(ray, beta)| (ray, beta / (1.0 - p_emitter)))
+                };
+                let mut photons = Vec::new();
+                if let Some((ray, power)) = emitted {
+                    self.trace_photon(ray, power * scale, &mut photons, &sample);
+                }
+                photons
+            })
+            .collect::<Vec<_>>();
+        PhotonMap::new(photons, radius)
+    }
+
+    /// Returns the ray entering the scene from the background through the disk of `radius` around `center`
+    /// and its throughput, which is the radiance times the area of the disk divided by the PDF of the direction.
+    ///
+    /// The direction is sampled from the light dimension of the first bounce, and the point on the disk from the BSDF one.
+    fn sample_background_ray(
+        &self,
+        center: Vec3,
+        radius: f64,
+        sample: &PixelSample,
+    ) -> Option<(Ray, Color)> {
+        let u_direction = sample.get(Dimension::Light(0));
+        let (w, radiance, pdf) = match &self.background {
+            Background::Environment(environment) => environment.sample(u_direction),
+            _ => {
+                let w = uniform_sphere(u_direction);
+                (w, self.background(w), 1.0 / (4.0 * PI))
+            }
+        };
+        if pdf <= 0.0 || radiance.is_black() {
+            return None;
+        }
+        let (u, v) = orthonormal_basis(w);
+        let (u_r, u_phi) = sample.get(Dimension::Bsdf(0));
+        let r = radius * u_r.sqrt();
+        let phi = 2.0 * PI * u_phi;
+        let origin = center + w * radius + u * (r * phi.cos()) + v * (r * phi.sin());
+        let beta = radiance * (PI * radius * radius / pdf);
+        Some((Ray::new(origin, -w), beta))
+    }
+
+    /// Returns the mean luminance of the background over all directions.
+    fn background_luminance(&self) -> f64 {
+        // The directions at the centers of cells of equal solid angle.
+        const N: usize = 16;
+
+        let mut sum = 0.0;
+        for i in 0..N {
+            for j in 0..2 * N {
+                let u = (
+                    (i as f64 + 0.5) / N as f64,
+                    (j as f64 + 0.5) / (2 * N) as f64,
+                );
+                sum += self.background(uniform_sphere(u)).luminance();
+            }
+        }
+        sum / (2 * N * N) as f64
+    }
+
+    /// Stores the photon at each surface hit by the ray, except surfaces of only delta lobes,
+    /// until it is absorbed or bounces `max_depth` times.
+    ///
+    /// The emission takes the dimensions of the first bounce, so the BSDF at the `i`-th hit is sampled from the next.
+    fn trace_photon(
+        &self,
+        mut ray: Ray,
+        mut power: Color,
+        photons: &mut Vec<Photon>,
+        sample: &PixelSample,
+    ) {
+        for bounce in 1..=self.max_depth {
+            let Some(info) = self
+                .world
+                .hit(&ray, Interval::from_val(T_MIN, f64::INFINITY))
+            else {
+                return;
+            };
+            let wo = -ray.direction.as_unit();
+            if !info.m.is_delta(&info) {
+                photons.push(Photon {
+                    p: info.p,
+                    wi: wo,
+                    power,
+                    axis: 0,
+                });
+            }
+            let (_, u_lobe) = sample.get(Dimension::Selection(bounce));
+            let u = sample.get(Dimension::Bsdf(bounce));
+            let Some(bsdf) = info.m.sample(wo, &info, [u_lobe, u.0, u.1]) else {
+                return;
+            };
+            power *= bsdf.weight();
+            if power.is_black() {
+                return;
+            }
+            ray = Ray::new(info.p, bsdf.wi);
+        }
+    }
+
+    /// Returns the color of the camera ray by photon mapping.
+    ///
+    /// The ray follows delta lobes, and the light reflected by the other lobes is estimated from photons at each hit.
+    /// Lights which are not geometry cannot emit photons, so they are sampled at each hit.
+    ///
+    /// # Arguments
+    /// * `ray`     - The ray from camera.
+    /// * `photons` - The photon map.
+    /// * `context` - The context of the sample to draw the samples of lights and BSDF.
+    pub(crate) fn trace_photon_mapping(
+        &self,
+        mut ray: Ray,
+        photons: &PhotonMap,
+        context: &SampleContext,
+    ) -> Color {
+        let mut color = Color::BLACK;
+        let mut beta = Color::WHITE;
+        for bounce in 0..self.max_depth {
+            let Some(info) = self
+                .world
+                .hit(&ray, Interval::from_val(T_MIN, f64::INFINITY))
+            else {
+                return color + beta * self.background(ray.direction);
+            };
+            let wo = -ray.direction.as_unit();
+            color += beta
+                * (info.m.emitted(info.u, info.v, info.p)
+                    + photons.estimate(wo, &info)
+                    + self.sample_lights(&ray, &info, context.sample_2d(Dimension::Light(bounce))));
+            let (_, u_lobe) = context.sample_2d(Dimension::Selection(bounce));
+            let u = context.sample_2d(Dimension::Bsdf(bounce));
+            // The other lobes are estimated by photons, so only delta lobes are followed.
+            match info.m.sample(wo, &info, [u_lobe, u.0, u.1]) {
+                Some(sample) if sample.lobe.is_delta() => {
+                    beta *= sample.weight();
+                    ray = Ray::new(info.p, sample.wi);
+                }
+                _ => break,
+            }
+        }
+        color
+    }
+}
@@ -0,0 +1,270 @@
+use std::time::{Duration, Instant};
+
+use image::RgbImage;
+
+use crate::render::integrator::PathIntegrator;
+use crate::render::{
+    CancelToken, Checkpoint, Film, Integrator, RenderMode, RenderOptions, Renderer, Scene,
+};
+use crate::sampler::Sampler;
+
+/// Conditions to stop progressive rendering, whichever is reached first.
+///
+/// If neither is set, rendering continues until cancelled.
+///
+/// # Arguments
+/// * `passes`  - The maximum number of passes.
+/// * `time`    - The wall-clock time budget.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Budget {
+    pub passes: Option<u32>,
+    pub time: Option<Duration>,
+}
+
+impl Budget {
+    /// Constructs `Budget` to stop after `passes` passes.
+    pub fn passes(passes: u32) -> Self {
+        Budget {
+            passes: Some(passes),
+            time: None,
+        }
+    }
+
+    /// Constructs `Budget` to stop after `time` has elapsed.
+    pub fn time(time: Duration) -> Self {
+        Budget {
+            passes: None,
+            time: Some(time),
+        }
+    }
+}
+
+/// A renderer to accumulate passes of samples into `Film`.
+///
+/// Each pass takes the same number of samples per pixel, continuing the sample indices of the previous passes.
+/// The current estimate is available between passes, and converges as passes are added.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use raytrs::camera::Camera;
+/// use raytrs::geometry::Vec3;
+/// use raytrs::render::{Budget, Scene};
+///
+/// let scene = Scene::new(Camera::from_lookat(
+///     Vec3::new(0.0, 0.0, 0.0),
+///     Vec3::new(0.0, 0.0, -1.0),
+///     Vec3::new(0.0, 1.0, 0.0),
+///     90.0,
+///     16,
+///     8,
+/// ));
+///
+/// let mut renderer = scene.progressive(2);
+/// let passes = renderer.run(&Budget::passes(3), None, |r| {
+///     assert_eq!(r.film().sample_count(0, 0), 2 * r.passes());
+/// });
+/// assert_eq!(passes, 3);
+/// assert_eq!(renderer.image().dimensions(), (16, 8));
+///
+/// // Continue rendering until 50 ms have passed.
+/// renderer.run(&Budget::time(Duration::from_millis(50)), None, |_| {});
+/// assert!(renderer.passes() >= 3);
+///
+/// // The first pass is completed even if the time budget is shorter.
+/// let mut renderer = scene.progressive(2);
+/// assert_eq!(renderer.run(&Budget::time(Duration::ZERO), None, |_| {}), 1);
+/// assert_eq!(renderer.film().sample_count(0, 0), 2);
+/// ```
+pub struct ProgressiveRenderer<'s> {
+    scene: &'s Scene,
+    film: Film,
+    samples_per_pass: usize,
+    passes: u32,
+    elapsed: Duration,
+    sampler: Box<dyn Sampler>,
+    integrator: &'s dyn Integrator,
+}
+
+impl<'s> ProgressiveRenderer<'s> {
+    /// Constructs `ProgressiveRenderer` with empty film.
+    ///
+    /// # Arguments
+    /// * `scene`               - The scene to render.
+    /// * `samples_per_pass`    - The number of samples per pixel in each pass, which is at least `1`.
+    pub fn new(scene: &'s Scene, samples_per_pass: usize) -> Self {
+        let samples_per_pass = samples_per_pass.max(1);
+        ProgressiveRenderer {
+            scene,
+            film: Film::new(scene.width(), scene.height()),
+            samples_per_pass,
+            passes: 0,
+            elapsed: Duration::ZERO,
+            sampler: scene.sampler().build(samples_per_pass, scene.seed()),
+            integrator: &PathIntegrator,
+        }
+    }
+
+    /// Sets the integrator of the passes rendered by `run`, which is the path tracer by default.
+    ///
+    /// The integrator is not recorded in checkpoints, so the same one must be set to resume.
+    pub fn with_integrator(mut self, integrator: &'s dyn Integrator) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Constructs `ProgressiveRenderer` continuing from the checkpoint.
+    ///
+    /// Returns an error, if the checkpoint is taken from another scene or with other settings.
+    ///
+    /// # Arguments
+    /// * `scene`       - The scene to render.
+    /// * `checkpoint`  - The checkpoint taken by `checkpoint`.
+    /// * `scene_hash`  - The hash of the scene, which must be the same as the checkpoint.
+    pub fn resume(
+        scene: &'s Scene,
+        checkpoint: Checkpoint,
+        scene_hash: u64,
+    ) -> Result<Self, String> {
+        let film = &checkpoint.film;
+        if checkpoint.scene_hash != scene_hash {
+            return Err("the checkpoint is taken from another scene".to_string());
+        }
+        if (film.width(), film.height()) != (scene.width(), scene.height()) {
+            return Err(format!(
+                "the checkpoint resolution {}x{} does not match {}x{}",
+                film.width(),
+                film.height(),
+                scene.width(),
+                scene.height()
+            ));
+        }
+        if checkpoint.seed != scene.seed() {
+            return Err(format!(
+                "the checkpoint seed {} does not match {}",
+                checkpoint.seed,
+                scene.seed()
+            ));
+        }
+        if checkpoint.sampler != scene.sampler() {
+            return Err(format!(
+                "the checkpoint sampler {:?} does not match {:?}",
+                checkpoint.sampler,
+                scene.sampler()
+            ));
+        }
+        let mut renderer = Self::new(scene, checkpoint.samples_per_pass);
+        renderer.film = checkpoint.film;
+        renderer.passes = checkpoint.passes;
+        renderer.elapsed = checkpoint.elapsed;
+        Ok(renderer)
+    }
+
+    /// Returns the checkpoint of the completed passes.
+    ///
+    /// # Arguments
+    /// * `scene_hash`  - The hash to identify the scene, e.g. `SceneFile::hash`.
+    pub fn checkpoint(&self, scene_hash: u64) -> Checkpoint {
+        Checkpoint {
+            scene_hash,
+            seed: self.scene.seed(),
+            sampler: self.scene.sampler(),
+            samples_per_pass: self.samples_per_pass,
+            passes: self.passes,
+            elapsed: self.elapsed,
+            film: self.film.clone(),
+        }
+    }
+
+    /// Returns the accumulated film.
+    pub fn film(&self) -> &Film {
+        &self.film
+    }
+
+    /// Returns 8-bit image of the current estimate.
+    pub fn image(&self) -> RgbImage {
+        self.film.to_image()
+    }
+
+    /// Returns the number of completed passes.
+    pub fn passes(&self) -> u32 {
+        self.passes
+    }
+
+    /// Returns the number of samples per pixel in each pass.
+    pub fn samples_per_pass(&self) -> usize {
+        self.samples_per_pass
+    }
+
+    /// Returns the total time spent on the completed passes.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Renders a pass and adds it to the film.
+    ///
+    /// Returns `false` without changing the film, if the pass is cancelled by the options.
+    ///
+    /// # Arguments
+    /// * `options` - The options to report progress of tiles, to cancel and to set the integrator, whose mode is ignored.
+    pub fn render_pass(&mut self, options: &RenderOptions) -> bool {
+        let start = Instant::now();
+        let offset = self.passes as usize * self.samples_per_pass;
+        let mode = RenderMode::AA(self.samples_per_pass);
+        let pass = self
+            .scene
+            .render_pass(options, &mode, offset, self.sampler.as_ref());
+        let samples =
+            self.samples_per_pass as u64 * self.film.width() as u64 * self.film.height() as u64;
+        if pass.total_samples() < samples {
+            // Some tiles are cancelled.
+            return false;
+        }
+        self.film.accumulate(&pass);
+        self.passes += 1;
+        self.elapsed += start.elapsed();
+        true
+    }
+
+    /// Renders passes until the budget is exhausted or cancelled, and returns the number of completed passes.
+    ///
+    /// The pass in progress when the time budget runs out or cancelled is discarded,
+    /// except that the first pass of an empty film ignores the time budget, so that the image is never left black.
+    ///
+    /// # Arguments
+    /// * `budget`  - The conditions to stop, which are counted from this call.
+    /// * `cancel`  - The optional token to stop rendering.
+    /// * `on_pass` - The callback called after each pass, e.g. to show the current estimate.
+    pub fn run<F>(&mut self, budget: &Budget, cancel: Option<&CancelToken>, mut on_pass: F) -> u32
+    where
+        F: FnMut(&Self),
+    {
+        let start = Instant::now();
+        let integrator = self.integrator;
+        let options = || {
+            let options = RenderOptions::new(RenderMode::AA(self.samples_per_pass))
+                .with_integrator(integrator);
+            match cancel {
+                Some(token) => options.with_cancel(token.clone()),
+                None => options,
+            }
+        };
+        let first = options();
+        let options = match budget.time {
+            Some(time) => options().with_deadline(start + time),
+            None => options(),
+        };
+
+        let mut passes = 0;
+        while budget.passes.is_none_or(|n| passes < n) {
+            let options = if self.passes == 0 { &first } else { &options };
+            if !self.render_pass(options) {
+                break;
+            }
+            passes += 1;
+            on_pass(self);
+        }
+        passes
+    }
+}
@@ -0,0 +1,18 @@
+/// Statistics of rendering accumulated in `Film`.
+///
+/// # Arguments
+/// * `terminated`  - The number of paths terminated by Russian roulette.
+/// * `clamped`     - The number of samples whose radiance is clamped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    pub terminated: u64,
+    pub clamped: u64,
+}
+
+impl RenderStats {
+    /// Adds the statistics of another film or tile.
+    pub(crate) fn merge(&mut self, other: &RenderStats) {
+        self.terminated += other.terminated;
+        self.clamped += other.clamped;
+    }
+}
@@ -0,0 +1,146 @@
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// A rectangle of pixels in `[x0, x1) x [y0, y1)` rendered by a worker at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x0: u32,
+    pub y0: u32,
+    pub x1: u32,
+    pub y1: u32,
+}
+
+/// Represents the order to render tiles.
+///
+/// # Variants
+/// * `Scanline`    - From the top-left tile, row by row.
+/// * `Spiral`      - From the center tile, spiraling outward.
+/// * `Hilbert`     - Along the Hilbert curve, where successive tiles are adjacent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TileOrder {
+    #[default]
+    Scanline,
+    Spiral,
+    Hilbert,
+}
+
+impl TileOrder {
+    /// Returns the tiles covering the image in this order.
+    ///
+    /// Tiles on the right and bottom edges are clipped by the image.
+    ///
+    /// # Arguments
+    /// * `width`   - The image width.
+    /// * `height`  - The image height.
+    /// * `size`    - The width and height of tiles, which is at least `1`.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::render::{Tile, TileOrder};
+    ///
+    /// let tiles = TileOrder::Spiral.tiles(96, 64, 32);
+    /// assert_eq!(tiles.len(), 6);
+    /// assert_eq!(tiles[0], Tile { x0: 32, y0: 0, x1: 64, y1: 32 });
+    ///
+    /// let tiles = TileOrder::Hilbert.tiles(100, 100, 25);
+    /// assert_eq!(tiles.len(), 16);
+    /// assert!(tiles.windows(2).all(|t| t[0].x0.abs_diff(t[1].x0) + t[0].y0.abs_diff(t[1].y0) == 25));
+    /// ```
+    pub fn tiles(&self, width: u32, height: u32, size: u32) -> Vec<Tile> {
+        let size = size.max(1);
+        let (nx, ny) = (width.div_ceil(size), height.div_ceil(size));
+        let tile = |(tx, ty): (u32, u32)| Tile {
+            x0: tx * size,
+            y0: ty * size,
+            x1: ((tx + 1) * size).min(width),
+            y1: ((ty + 1) * size).min(height),
+        };
+        let indices = match self {
+            TileOrder::Scanline => (0..ny)
+                .flat_map(|ty| (0..nx).map(move |tx| (tx, ty)))
+                .collect(),
+            TileOrder::Spiral => spiral(nx, ny),
+            TileOrder::Hilbert => hilbert(nx, ny),
+        };
+        indices.into_iter().map(tile).collect()
+    }
+}
+
+impl FromStr for TileOrder {
+    type Err = String;
+
+    /// Parses the tile order from `scanline`, `spiral` or `hilbert`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "scanline" => Ok(TileOrder::Scanline),
+            "spiral" => Ok(TileOrder::Spiral),
+            "hilbert" => Ok(TileOrder::Hilbert),
+            _ => Err(format!(
+                "unknown tile order `{}`, expected one of scanline, spiral, hilbert",
+                s
+            )),
+        }
+    }
+}
+
+/// Returns the indices of `nx` x `ny` tiles walking right, down, left and up from the center,
+/// where the length of straight runs increases every two turns.
+fn spiral(nx: u32, ny: u32) -> Vec<(u32, u32)> {
+    const DIRECTIONS: [(i64, i64); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+    let total = (nx * ny) as usize;
+    let mut indices = Vec::with_capacity(total);
+    let (mut x, mut y) = (((nx as i64) - 1) / 2, ((ny as i64) - 1) / 2);
+    let mut run = 1;
+    let mut direction = 0;
+    while indices.len() < total {
+        for _ in 0..2 {
+            let (dx, dy) = DIRECTIONS[direction];
+            for _ in 0..run {
+                if (0..nx as i64).contains(&x) && (0..ny as i64).contains(&y) {
+                    indices.push((x as u32, y as u32));
+                }
+                x += dx;
+                y += dy;
+            }
+            direction = (direction + 1) % 4;
+        }
+        run += 1;
+    }
+    indices
+}
+
+/// Returns the indices of `nx` x `ny` tiles along the Hilbert curve covering them,
+/// where the curve is traversed on the smallest power-of-two grid and the outside is skipped.
+fn hilbert(nx: u32, ny: u32) -> Vec<(u32, u32)> {
+    let n = nx.max(ny).next_power_of_two() as u64;
+    (0..n * n)
+        .map(|d| hilbert_d2xy(n, d))
+        .filter(|&(x, y)| x < nx && y < ny)
+        .collect()
+}
+
+/// Converts the distance `d` along the Hilbert curve on the `n` x `n` grid to the position.
+fn hilbert_d2xy(n: u64, d: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0, 0);
+    let mut t = d;
+    let mut s = 1;
+    while s < n {
+        let rx = 1 & (t / 2);
+        let ry = 1 & (t ^ rx);
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+    (x as u32, y as u32)
+}
@@ -0,0 +1,213 @@
+pub(crate) mod blue_noise;
+pub(crate) mod halton;
+pub(crate) mod independent;
+pub(crate) mod sobol;
+pub(crate) mod stratified;
+
+use std::fmt::Debug;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use self::blue_noise::BlueNoiseSampler as _BlueNoiseSampler;
+use self::halton::HaltonSampler as _HaltonSampler;
+use self::independent::IndependentSampler as _IndependentSampler;
+use self::sobol::SobolSampler as _SobolSampler;
+use self::stratified::StratifiedSampler as _StratifiedSampler;
+
+pub type BlueNoiseSampler = _BlueNoiseSampler;
+pub type HaltonSampler = _HaltonSampler;
+pub type IndependentSampler = _IndependentSampler;
+pub type SobolSampler = _SobolSampler;
+pub type StratifiedSampler = _StratifiedSampler;
+
+/// Represents the usage of 2D sample dimensions.
+///
+/// Each bounce has its own dimensions, so the samples of a bounce are stratified across the samples of the pixel
+/// regardless of the choices at the other bounces.
+///
+/// # Variants
+/// * `Pixel`       - The position in the pixel.
+/// * `Light`       - The position on a light at the specified bounce.
+/// * `Bsdf`        - The direction sampled from BSDF at the specified bounce.
+/// * `Selection`   - The light chosen by the first element and the lobe of BSDF chosen by the second at the specified bounce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Pixel,
+    Light(u32),
+    Bsdf(u32),
+    Selection(u32),
+}
+
+impl Dimension {
+    /// Returns the index of 2D dimension.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::sampler::Dimension;
+    ///
+    /// assert_eq!(Dimension::Pixel.index(), 0);
+    /// assert_eq!(Dimension::Light(0).index(), 1);
+    /// assert_eq!(Dimension::Bsdf(1).index(), 5);
+    /// assert_eq!(Dimension::Selection(1).index(), 6);
+    /// ```
+    pub fn index(&self) -> u32 {
+        match *self {
+            Dimension::Pixel => 0,
+            Dimension::Light(bounce) => 1 + 3 * bounce,
+            Dimension::Bsdf(bounce) => 2 + 3 * bounce,
+            Dimension::Selection(bounce) => 3 + 3 * bounce,
+        }
+    }
+}
+
+/// A sample of a pixel, which draws 2D samples of dimensions from the sampler.
+///
+/// # Arguments
+/// * `sampler` - The sampler.
+/// * `pixel`   - The pixel position.
+/// * `index`   - The sample index in the pixel.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{Dimension, PixelSample, SamplerType};
+///
+/// let sampler = SamplerType::Sobol.build(4, 0);
+/// let sample = PixelSample::new(sampler.as_ref(), (1, 2), 3);
+/// assert_eq!(sample.get(Dimension::Bsdf(0)), sampler.sample((1, 2), 3, Dimension::Bsdf(0)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct PixelSample<'a> {
+    sampler: &'a dyn Sampler,
+    pixel: (u32, u32),
+    index: u32,
+}
+
+impl<'a> PixelSample<'a> {
+    /// Constructs `PixelSample`.
+    ///
+    /// # Arguments
+    /// * `sampler` - The sampler.
+    /// * `pixel`   - The pixel position.
+    /// * `index`   - The sample index in the pixel.
+    pub fn new(sampler: &'a dyn Sampler, pixel: (u32, u32), index: u32) -> Self {
+        PixelSample {
+            sampler,
+            pixel,
+            index,
+        }
+    }
+
+    /// Returns the 2D sample for the usage.
+    pub fn get(&self, dimension: Dimension) -> (f64, f64) {
+        self.sampler.sample(self.pixel, self.index, dimension)
+    }
+}
+
+/// A trait to generate sample points in `[0.0, 1.0)^2`.
+///
+/// Samplers are pure functions of the pixel, the sample index and the dimension,
+/// so the result does not depend on the order of samples to be computed.
+pub trait Sampler: Sync + Send + Debug {
+    /// Returns the 2D sample.
+    ///
+    /// # Arguments
+    /// * `pixel`   - The pixel position.
+    /// * `index`   - The sample index in the pixel.
+    /// * `dim`     - The index of 2D dimension.
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64);
+
+    /// Returns the 1D sample, which is the first element of the 2D sample.
+    fn get_1d(&self, pixel: (u32, u32), index: u32, dim: u32) -> f64 {
+        self.get_2d(pixel, index, dim).0
+    }
+
+    /// Returns the 2D sample for the usage.
+    fn sample(&self, pixel: (u32, u32), index: u32, dimension: Dimension) -> (f64, f64) {
+        self.get_2d(pixel, index, dimension.index())
+    }
+}
+
+/// Represents the type of sampler.
+///
+/// # Variants
+/// * `Independent` - Uniform random samples.
+/// * `Stratified`  - Jittered samples in strata.
+/// * `Halton`      - Randomized Halton sequence.
+/// * `Sobol`       - Owen-scrambled Sobol sequence.
+/// * `BlueNoise`   - Sobol sequence dithered by blue noise across pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplerType {
+    #[default]
+    Independent,
+    Stratified,
+    Halton,
+    Sobol,
+    BlueNoise,
+}
+
+impl SamplerType {
+    /// Constructs the sampler.
+    ///
+    /// # Arguments
+    /// * `samples` - The number of samples per pixel.
+    /// * `seed`    - The seed to randomize samples.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::sampler::{Dimension, SamplerType};
+    ///
+    /// let sampler = SamplerType::Sobol.build(16, 0);
+    /// let (u, v) = sampler.sample((0, 0), 0, Dimension::Pixel);
+    /// assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+    /// ```
+    pub fn build(&self, samples: usize, seed: u64) -> Box<dyn Sampler> {
+        match self {
+            SamplerType::Independent => Box::new(IndependentSampler::new(seed)),
+            SamplerType::Stratified => Box::new(StratifiedSampler::new(samples, seed)),
+            SamplerType::Halton => Box::new(HaltonSampler::new(seed)),
+            SamplerType::Sobol => Box::new(SobolSampler::new(seed)),
+            SamplerType::BlueNoise => Box::new(BlueNoiseSampler::new(seed)),
+        }
+    }
+}
+
+impl FromStr for SamplerType {
+    type Err = String;
+
+    /// Parses the sampler type from `independent`, `stratified`, `halton`, `sobol` or `blue_noise`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "independent" => Ok(SamplerType::Independent),
+            "stratified" => Ok(SamplerType::Stratified),
+            "halton" => Ok(SamplerType::Halton),
+            "sobol" => Ok(SamplerType::Sobol),
+            "blue_noise" => Ok(SamplerType::BlueNoise),
+            _ => Err(format!(
+                "unknown sampler `{}`, expected one of independent, stratified, halton, sobol, blue_noise",
+                s
+            )),
+        }
+    }
+}
+
+/// Returns the hash of values, which is used to decorrelate pixels and dimensions.
+pub(crate) fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x243F_6A88_85A3_08D3, |h, &v| {
+        let mut z = (h ^ v).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    })
+}
+
+/// Returns `u32` mapped to `[0.0, 1.0)`.
+pub(crate) fn to_unit(x: u32) -> f64 {
+    x as f64 / 4294967296.0
+}
+
+/// Returns `u64` mapped to `[0.0, 1.0)`.
+pub(crate) fn to_unit_u64(x: u64) -> f64 {
+    (x >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,142 @@
+use std::sync::OnceLock;
+
+use crate::sampler::sobol::owen_sobol_2d;
+use crate::sampler::{hash, to_unit, Sampler};
+use crate::utils::Rng;
+
+/// The width and height of the blue noise mask.
+const MASK_SIZE: usize = 64;
+
+/// A sampler generating Sobol sequence dithered by blue noise across pixels.
+///
+/// All pixels share the same Owen-scrambled Sobol sequence,
+/// which is rotated toroidally by the values of a blue noise mask at each pixel.
+/// Thus, the error of neighboring pixels is decorrelated and distributed as blue noise,
+/// which is less visible than white noise at low sample counts.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{BlueNoiseSampler, Sampler};
+///
+/// let sampler = BlueNoiseSampler::new(0);
+/// let (u, v) = sampler.get_2d((10, 20), 0, 0);
+/// assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlueNoiseSampler {
+    seed: u64,
+    mask: &'static [f64],
+}
+
+impl BlueNoiseSampler {
+    /// Constructs `BlueNoiseSampler`.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed of scrambling and mask offsets.
+    pub fn new(seed: u64) -> Self {
+        static MASK: OnceLock<Vec<f64>> = OnceLock::new();
+        BlueNoiseSampler {
+            seed,
+            mask: MASK.get_or_init(|| void_and_cluster(MASK_SIZE)),
+        }
+    }
+
+    /// Returns the mask value at the pixel shifted by `offset`.
+    fn mask(&self, pixel: (u32, u32), offset: u64) -> f64 {
+        let x = (pixel.0 as usize + (offset as usize % MASK_SIZE)) % MASK_SIZE;
+        let y = (pixel.1 as usize + ((offset >> 32) as usize % MASK_SIZE)) % MASK_SIZE;
+        self.mask[y * MASK_SIZE + x]
+    }
+}
+
+impl Sampler for BlueNoiseSampler {
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64) {
+        let h = hash(&[self.seed, dim as u64]);
+        let (u, v) = owen_sobol_2d(index, h);
+        let u = to_unit(u) + self.mask(pixel, hash(&[h, 0]));
+        let v = to_unit(v) + self.mask(pixel, hash(&[h, 1]));
+        (u.fract(), v.fract())
+    }
+}
+
+/// Returns the blue noise mask of `size` x `size` generated with void-and-cluster method by Ulichney.
+///
+/// Each value is the rank of the pixel in `[0.0, 1.0)`, where the pixels of lower rank are spread uniformly.
+fn void_and_cluster(size: usize) -> Vec<f64> {
+    const SIGMA: f64 = 1.5;
+
+    let n = size * size;
+    // The Gaussian energy of toroidal offsets.
+    let kernel = (0..n)
+        .map(|i| {
+            let d = |c: usize| c.min(size - c) as f64;
+            let (dx, dy) = (d(i % size), d(i / size));
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pattern = vec![false; n];
+    let mut energy = vec![0.0; n];
+    let toggle = |pattern: &mut Vec<bool>, energy: &mut Vec<f64>, p: usize| {
+        pattern[p] = !pattern[p];
+        let sign = if pattern[p] { 1.0 } else { -1.0 };
+        let (px, py) = (p % size, p / size);
+        for (q, e) in energy.iter_mut().enumerate() {
+            let dx = (q % size + size - px) % size;
+            let dy = (q / size + size - py) % size;
+            *e += sign * kernel[dy * size + dx];
+        }
+    };
+    // The tightest cluster is the one with the highest energy, and the largest void is the zero with the lowest.
+    let tightest_cluster = |pattern: &[bool], energy: &[f64]| {
+        (0..n)
+            .filter(|&p| pattern[p])
+            .max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap()
+    };
+    let largest_void = |pattern: &[bool], energy: &[f64]| {
+        (0..n)
+            .filter(|&p| !pattern[p])
+            .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+            .unwrap()
+    };
+
+    // Initial binary pattern with uniformly distributed ones.
+    let mut rng = Rng::new(0);
+    let ones = n / 10;
+    let mut count = 0;
+    while count < ones {
+        let p = (rng.next_u64() % n as u64) as usize;
+        if !pattern[p] {
+            toggle(&mut pattern, &mut energy, p);
+            count += 1;
+        }
+    }
+    for _ in 0..n {
+        let cluster = tightest_cluster(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, cluster);
+        let void = largest_void(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut rank = vec![0; n];
+    // Rank the initial ones by removing the tightest cluster.
+    let (mut p1, mut e1) = (pattern.clone(), energy.clone());
+    for r in (0..ones).rev() {
+        let cluster = tightest_cluster(&p1, &e1);
+        toggle(&mut p1, &mut e1, cluster);
+        rank[cluster] = r;
+    }
+    // Rank the rest by filling the largest void.
+    for r in ones..n {
+        let void = largest_void(&pattern, &energy);
+        toggle(&mut pattern, &mut energy, void);
+        rank[void] = r;
+    }
+    rank.into_iter()
+        .map(|r| (r as f64 + 0.5) / n as f64)
+        .collect()
+}
@@ -0,0 +1,74 @@
+use crate::sampler::{hash, to_unit_u64, Sampler};
+
+/// The prime bases of Halton sequence, two for each 2D dimension.
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131,
+];
+
+/// A sampler generating randomized Halton sequence.
+///
+/// The sequence is rotated toroidally for each pixel and dimension (Cranley-Patterson rotation).
+/// Dimensions beyond the prime table reuse the bases with different rotations.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{HaltonSampler, Sampler};
+///
+/// let sampler = HaltonSampler::new(0);
+/// let (u, v) = sampler.get_2d((3, 4), 5, 0);
+/// assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HaltonSampler {
+    seed: u64,
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl HaltonSampler {
+    /// Constructs `HaltonSampler`.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed of rotations.
+    pub fn new(seed: u64) -> Self {
+        HaltonSampler { seed }
+    }
+
+    /// Returns the radical inverse of `index` in `base`.
+    ///
+    /// With the digits $d_k$ of `index` in `base` $b$, it is expressed as follows.
+    ///
+    /// $$
+    /// \Phi_b(i) = \sum_{k} d_k b^{-k-1}
+    /// $$
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::sampler::HaltonSampler;
+    ///
+    /// assert_eq!(HaltonSampler::radical_inverse(2, 1), 0.5);
+    /// assert_eq!(HaltonSampler::radical_inverse(2, 3), 0.75);
+    /// assert_eq!(HaltonSampler::radical_inverse(3, 1), 1.0 / 3.0);
+    /// ```
+    pub fn radical_inverse(base: u32, mut index: u32) -> f64 {
+        let inv_base = 1.0 / base as f64;
+        let mut inv = inv_base;
+        let mut result = 0.0;
+        while index > 0 {
+            result += (index % base) as f64 * inv;
+            index /= base;
+            inv *= inv_base;
+        }
+        result
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64) {
+        let i = (2 * dim as usize) % PRIMES.len();
+        let h = hash(&[self.seed, pixel.0 as u64, pixel.1 as u64, dim as u64]);
+        let u = Self::radical_inverse(PRIMES[i], index) + to_unit_u64(h);
+        let v = Self::radical_inverse(PRIMES[i + 1], index) + to_unit_u64(hash(&[h]));
+        (u.fract(), v.fract())
+    }
+}
@@ -0,0 +1,39 @@
+use crate::sampler::{hash, to_unit_u64, Sampler};
+
+/// A sampler generating uniform random samples independently.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{IndependentSampler, Sampler};
+///
+/// let sampler = IndependentSampler::new(0);
+/// assert_eq!(sampler.get_2d((1, 2), 3, 0), sampler.get_2d((1, 2), 3, 0));
+/// assert_ne!(sampler.get_2d((1, 2), 3, 0), sampler.get_2d((1, 2), 3, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct IndependentSampler {
+    seed: u64,
+}
+
+impl IndependentSampler {
+    /// Constructs `IndependentSampler`.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed of random values.
+    pub fn new(seed: u64) -> Self {
+        IndependentSampler { seed }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64) {
+        let h = hash(&[
+            self.seed,
+            pixel.0 as u64,
+            pixel.1 as u64,
+            index as u64,
+            dim as u64,
+        ]);
+        (to_unit_u64(h), to_unit_u64(hash(&[h])))
+    }
+}
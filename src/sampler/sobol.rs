@@ -0,0 +1,82 @@
+use crate::sampler::{hash, to_unit, Sampler};
+
+/// A sampler generating Owen-scrambled Sobol sequence.
+///
+/// Each 2D dimension uses the first two dimensions of Sobol sequence,
+/// whose indices are shuffled and whose values are scrambled with hash-based Owen scrambling by Burley.
+/// The scrambling keeps the stratification of Sobol sequence, so every power of two samples is well distributed.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{Sampler, SobolSampler};
+///
+/// let sampler = SobolSampler::new(0);
+/// let mut quadrants = (0..4)
+///     .map(|i| {
+///         let (u, v) = sampler.get_2d((0, 0), i, 1);
+///         (u < 0.5) as usize + 2 * (v < 0.5) as usize
+///     })
+///     .collect::<Vec<_>>();
+/// quadrants.sort();
+/// assert_eq!(quadrants, vec![0, 1, 2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SobolSampler {
+    seed: u64,
+}
+
+impl SobolSampler {
+    /// Constructs `SobolSampler`.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed of scrambling.
+    pub fn new(seed: u64) -> Self {
+        SobolSampler { seed }
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64) {
+        let h = hash(&[self.seed, pixel.0 as u64, pixel.1 as u64, dim as u64]);
+        let (u, v) = owen_sobol_2d(index, h);
+        (to_unit(u), to_unit(v))
+    }
+}
+
+/// Returns the 2D point of the Owen-scrambled and shuffled Sobol sequence as fixed-point numbers.
+pub(crate) fn owen_sobol_2d(index: u32, seed: u64) -> (u32, u32) {
+    let index = nested_uniform_scramble(index, hash(&[seed, 0]) as u32);
+    (
+        nested_uniform_scramble(index.reverse_bits(), hash(&[seed, 1]) as u32),
+        nested_uniform_scramble(sobol_1(index), hash(&[seed, 2]) as u32),
+    )
+}
+
+/// Returns the second dimension of Sobol sequence, whose generator matrix is the Pascal matrix.
+fn sobol_1(mut index: u32) -> u32 {
+    let mut v = 1u32 << 31;
+    let mut result = 0;
+    while index != 0 {
+        if index & 1 != 0 {
+            result ^= v;
+        }
+        index >>= 1;
+        v ^= v >> 1;
+    }
+    result
+}
+
+/// Returns `x` scrambled with the nested uniform scrambling, which is equivalent to Owen scrambling.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+/// Returns the permutation of `x`, where each bit only depends on the lower bits.
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x = x.wrapping_add(seed);
+    x ^= x.wrapping_mul(0x6c50b47c);
+    x ^= x.wrapping_mul(0xb82f1e52);
+    x ^= x.wrapping_mul(0xc7afe638);
+    x ^= x.wrapping_mul(0x8d22f6e6);
+    x
+}
@@ -0,0 +1,91 @@
+use crate::sampler::{hash, to_unit_u64, Sampler};
+
+/// A sampler generating jittered samples in the grid of strata.
+///
+/// The pixel is divided into about `samples` strata, and each sample is placed in a different stratum.
+/// Strata are shuffled for each pixel and dimension to avoid correlation between dimensions.
+///
+/// # Examples
+/// ```
+/// use raytrs::sampler::{Sampler, StratifiedSampler};
+///
+/// let sampler = StratifiedSampler::new(4, 0);
+/// let mut quadrants = (0..4)
+///     .map(|i| {
+///         let (u, v) = sampler.get_2d((0, 0), i, 0);
+///         (u < 0.5) as usize + 2 * (v < 0.5) as usize
+///     })
+///     .collect::<Vec<_>>();
+/// quadrants.sort();
+/// assert_eq!(quadrants, vec![0, 1, 2, 3]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler {
+    nx: u32,
+    ny: u32,
+    seed: u64,
+}
+
+impl StratifiedSampler {
+    /// Constructs `StratifiedSampler`.
+    ///
+    /// # Arguments
+    /// * `samples` - The number of samples per pixel.
+    /// * `seed`    - The seed of shuffling and jittering.
+    pub fn new(samples: usize, seed: u64) -> Self {
+        let samples = samples.max(1) as u32;
+        let nx = (samples as f64).sqrt().ceil() as u32;
+        let ny = samples.div_ceil(nx);
+        StratifiedSampler { nx, ny, seed }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn get_2d(&self, pixel: (u32, u32), index: u32, dim: u32) -> (f64, f64) {
+        let n = self.nx * self.ny;
+        let h = hash(&[self.seed, pixel.0 as u64, pixel.1 as u64, dim as u64]);
+        let stratum = permutation_element(index % n, n, h as u32);
+        let jitter = hash(&[h, index as u64]);
+        let (sx, sy) = (stratum % self.nx, stratum / self.nx);
+        (
+            (sx as f64 + to_unit_u64(jitter)) / self.nx as f64,
+            (sy as f64 + to_unit_u64(hash(&[jitter]))) / self.ny as f64,
+        )
+    }
+}
+
+/// Returns the `i`-th element of the random permutation of `[0, l)` determined by `p`.
+///
+/// This is the hash-based permutation by Kensler, which needs no table.
+fn permutation_element(mut i: u32, l: u32, p: u32) -> u32 {
+    let mut w = l - 1;
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+    loop {
+        i ^= p;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= p >> 16;
+        i ^= (i & w) >> 4;
+        i ^= p >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= p >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | p >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+        if i < l {
+            break;
+        }
+    }
+    ((i as u64 + p as u64) % l as u64) as u32
+}
@@ -1,4 +1,8 @@
+pub(crate) mod aabb;
+pub(crate) mod bvh;
+pub(crate) mod mesh;
 pub(crate) mod sphere;
+pub(crate) mod triangle;
 
 use std::sync::Arc;
 
@@ -7,10 +11,20 @@ use crate::interval::Interval;
 use crate::material::Material;
 use crate::ray::Ray;
 
+use self::aabb::Aabb as _Aabb;
+use self::bvh::Bvh as _Bvh;
+use self::mesh::Mesh as _Mesh;
+use self::mesh::TriangleMesh as _TriangleMesh;
 use self::sphere::Sphere as _Sphere;
+use self::triangle::Triangle as _Triangle;
 
+pub type Aabb = _Aabb;
+pub type Bvh = _Bvh;
+pub type Mesh = _Mesh;
+pub type TriangleMesh = _TriangleMesh;
 #[cfg_attr(doc, katexit::katexit)]
 pub type Sphere = _Sphere;
+pub type Triangle = _Triangle;
 
 /// A container to store hit information
 ///
@@ -58,6 +72,73 @@ pub trait Shape: Sync {
     /// * `ray` - A `Ray` instance.
     /// * `interval` - Interval of the ray.
     fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo>;
+
+    /// Returns the axis-aligned bounding box of the object, which is `Aabb::INFINITE` by default.
+    ///
+    /// Objects without bounds are never culled by hierarchies, so bounded objects should override it.
+    fn bounding_box(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
+    /// Returns the number of bounding boxes and primitives tested to find the closest hit of the ray,
+    /// which is `1` for primitives by default.
+    ///
+    /// This is used to visualize the cost of traversing hierarchies.
+    ///
+    /// # Arguments
+    /// * `ray` - A `Ray` instance.
+    /// * `interval` - Interval of the ray.
+    fn traversal_cost(&self, _ray: &Ray, _interval: Interval) -> u32 {
+        1
+    }
+
+    /// Calls `f` with each material of the object, which does nothing by default.
+    ///
+    /// This is used to number materials for the material ID AOV.
+    fn for_each_material(&self, _f: &mut dyn FnMut(&Arc<dyn Material>)) {}
+
+    /// Returns copies of the primitives with emissive materials, which is empty by default.
+    ///
+    /// The primitives are sampled as lights by `Emitter`.
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        Vec::new()
+    }
+}
+
+/// A trait for primitives sampled as lights, which are returned by `Shape::emitters`.
+pub trait Emitter: Shape {
+    /// Returns a random point on the surface as `HitInfo` and its PDF in area,
+    /// or `None` if the surface is degenerate.
+    ///
+    /// The point is uniformly distributed, so the PDF is the reciprocal of `area`.
+    /// This is used to start light paths from emissive primitives.
+    ///
+    /// # Arguments
+    /// * `u`   - The uniform 2D sample in `[0, 1)^2`.
+    fn sample_surface(&self, u: (f64, f64)) -> Option<(HitInfo, f64)>;
+
+    /// Returns the surface area of the object.
+    fn area(&self) -> f64;
+
+    /// Returns the power emitted by the object.
+    ///
+    /// This is estimated from the emission at a point, and used to select emitters to sample.
+    fn power(&self) -> f64;
+
+    /// Returns the PDF in solid angle of `random_direction` sampling `direction` from `origin`,
+    /// which is `0.0` if the direction misses the object.
+    ///
+    /// # Arguments
+    /// * `origin`      - The point to see the object.
+    /// * `direction`   - The direction from `origin`.
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64;
+
+    /// Returns a random direction from `origin` toward the object.
+    ///
+    /// # Arguments
+    /// * `origin`  - The point to see the object.
+    /// * `u`       - The uniform 2D sample in `[0, 1)^2`.
+    fn random_direction(&self, origin: Vec3, u: (f64, f64)) -> Vec3;
 }
 
 /// A container to store objects in the world.
@@ -65,6 +146,12 @@ pub struct ShapeList {
     pub objects: Vec<Box<dyn Shape>>,
 }
 
+impl Default for ShapeList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ShapeList {
     /// Constructs `ShapeList`.
     ///
@@ -99,7 +186,7 @@ impl ShapeList {
     /// let sphere = Sphere::new(
     ///     Vec3::new(0.0, -1000.0, 0.0),
     ///     1000.0,
-    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::random())))),
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
     /// );
     ///
     /// world.push(Box::new(sphere));
@@ -109,17 +196,55 @@ impl ShapeList {
     }
 }
 
-impl Shape for ShapeList {
-    fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
-        let mut hit_info: Option<HitInfo> = None;
+impl ShapeList {
+    /// Returns the index of the closest object hit by the ray and `HitInfo`.
+    ///
+    /// # Arguments
+    /// * `ray`         - A `Ray` instance.
+    /// * `interval`    - Interval of the ray.
+    pub fn hit_object(&self, ray: &Ray, interval: Interval) -> Option<(usize, HitInfo)> {
+        let mut hit_info: Option<(usize, HitInfo)> = None;
         let mut closest_so_far = interval.max;
 
-        for obj in &self.objects {
+        for (i, obj) in self.objects.iter().enumerate() {
             if let Some(info) = obj.hit(ray, Interval::from_val(interval.min, closest_so_far)) {
                 closest_so_far = info.t;
-                hit_info = Some(info);
+                hit_info = Some((i, info));
             }
         }
         hit_info
     }
 }
+
+impl Shape for ShapeList {
+    fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
+        self.hit_object(ray, interval).map(|(_, info)| info)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects
+            .iter()
+            .fold(Aabb::EMPTY, |acc, obj| acc.union(&obj.bounding_box()))
+    }
+
+    fn traversal_cost(&self, ray: &Ray, interval: Interval) -> u32 {
+        let mut cost = 0;
+        let mut closest_so_far = interval.max;
+        for obj in &self.objects {
+            let interval = Interval::from_val(interval.min, closest_so_far);
+            cost += obj.traversal_cost(ray, interval);
+            if let Some(info) = obj.hit(ray, interval) {
+                closest_so_far = info.t;
+            }
+        }
+        cost
+    }
+
+    fn for_each_material(&self, f: &mut dyn FnMut(&Arc<dyn Material>)) {
+        self.objects.iter().for_each(|obj| obj.for_each_material(f));
+    }
+
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        self.objects.iter().flat_map(|obj| obj.emitters()).collect()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::ray::Ray;
+
+/// An axis-aligned bounding box.
+///
+/// # Arguments
+/// * `min` - The minimum corner.
+/// * `max` - The maximum corner.
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// An empty box, which is the identity of `union`.
+    pub const EMPTY: Aabb = Aabb {
+        min: Vec3::splat(f64::INFINITY),
+        max: Vec3::splat(-f64::INFINITY),
+    };
+
+    /// An infinite box, which every ray passes through.
+    pub const INFINITE: Aabb = Aabb {
+        min: Vec3::splat(-f64::INFINITY),
+        max: Vec3::splat(f64::INFINITY),
+    };
+
+    /// Constructs `Aabb` from two corners.
+    ///
+    /// # Arguments
+    /// * `a` - A corner.
+    /// * `b` - The opposite corner.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::shape::Aabb;
+    ///
+    /// let aabb = Aabb::new(Vec3::ones(), -Vec3::ones());
+    /// assert_eq!(aabb.min, -Vec3::ones());
+    /// ```
+    pub fn new(a: Vec3, b: Vec3) -> Self {
+        Aabb {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, rhs: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(rhs.min),
+            max: self.max.max(rhs.max),
+        }
+    }
+
+    /// Returns the smallest box containing the box and the point `p`.
+    pub fn grow(&self, p: Vec3) -> Aabb {
+        Aabb {
+            min: self.min.min(p),
+            max: self.max.max(p),
+        }
+    }
+
+    /// Returns the center of the box.
+    pub fn centroid(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns the index of the longest axis.
+    pub fn longest_axis(&self) -> usize {
+        let d = self.max - self.min;
+        if d[0] > d[1] && d[0] > d[2] {
+            0
+        } else if d[1] > d[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Returns the surface area of the box.
+    pub fn surface_area(&self) -> f64 {
+        let d = self.max - self.min;
+        if d[0] < 0.0 {
+            return 0.0;
+        }
+        2.0 * (d[0] * d[1] + d[1] * d[2] + d[2] * d[0])
+    }
+
+    /// Returns `true`, if the ray passes through the box within `interval`.
+    ///
+    /// # Arguments
+    /// * `ray`         - A `Ray` instance.
+    /// * `interval`    - Interval of the ray.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::interval::Interval;
+    /// use raytrs::ray::Ray;
+    /// use raytrs::shape::Aabb;
+    ///
+    /// let aabb = Aabb::new(Vec3::ones(), -Vec3::ones());
+    /// let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+    /// assert!(aabb.hit(&ray, Interval::from_val(0.0, f64::INFINITY)));
+    /// ```
+    pub fn hit(&self, ray: &Ray, interval: Interval) -> bool {
+        let mut t_min = interval.min;
+        let mut t_max = interval.max;
+        for axis in 0..3 {
+            let inv = ray.direction()[axis].recip();
+            let mut t0 = (self.min[axis] - ray.origin()[axis]) * inv;
+            let mut t1 = (self.max[axis] - ray.origin()[axis]) * inv;
+            if inv < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max < t_min {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,226 @@
+use std::sync::Arc;
+
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::shape::aabb::Aabb;
+use crate::shape::{Emitter, HitInfo, Shape};
+
+/// The maximum number of objects stored in a leaf node.
+const MAX_LEAF_SIZE: usize = 2;
+
+/// A node of `Bvh`.
+///
+/// Interior nodes store the index of their right child, the left child always follows its parent.
+/// Leaf nodes store the range of objects in `Bvh::objects`.
+#[derive(Debug)]
+enum BvhNode {
+    Interior {
+        bbox: Aabb,
+        right: usize,
+    },
+    Leaf {
+        bbox: Aabb,
+        start: usize,
+        end: usize,
+    },
+}
+
+impl BvhNode {
+    fn bbox(&self) -> &Aabb {
+        match self {
+            BvhNode::Interior { bbox, .. } => bbox,
+            BvhNode::Leaf { bbox, .. } => bbox,
+        }
+    }
+}
+
+/// A bounding volume hierarchy to accelerate ray intersection with many objects.
+///
+/// Objects are split at the median of their centroids along the longest axis.
+pub struct Bvh {
+    objects: Vec<Box<dyn Shape>>,
+    nodes: Vec<BvhNode>,
+}
+
+impl Bvh {
+    /// Constructs `Bvh` from objects.
+    ///
+    /// # Arguments
+    /// * `objects` - Any objects can be hit.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::interval::Interval;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::ray::Ray;
+    /// use raytrs::shape::{Bvh, Shape, Sphere};
+    ///
+    /// let material = Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::WHITE))));
+    /// let objects: Vec<Box<dyn Shape>> = (0..10)
+    ///     .map(|i| {
+    ///         Box::new(Sphere::new(Vec3::new(i as f64, 0.0, 0.0), 0.25, material.clone()))
+    ///             as Box<dyn Shape>
+    ///     })
+    ///     .collect();
+    /// let bvh = Bvh::new(objects);
+    ///
+    /// let ray = Ray::new(Vec3::new(3.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+    /// let info = bvh.hit(&ray, Interval::from_val(0.0, f64::INFINITY)).unwrap();
+    /// assert!((info.t - 4.75).abs() < 1e-8);
+    /// ```
+    pub fn new(objects: Vec<Box<dyn Shape>>) -> Self {
+        let mut items = objects
+            .into_iter()
+            .map(|obj| {
+                let bbox = obj.bounding_box();
+                (bbox.centroid(), bbox, obj)
+            })
+            .collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            Self::build(&mut items, 0, &mut nodes);
+        }
+        let objects = items.into_iter().map(|(_, _, obj)| obj).collect();
+        Bvh { objects, nodes }
+    }
+
+    /// Builds nodes recursively for `items` whose first element is stored at `offset`.
+    fn build(
+        items: &mut [(Vec3, Aabb, Box<dyn Shape>)],
+        offset: usize,
+        nodes: &mut Vec<BvhNode>,
+    ) -> usize {
+        let bbox = items
+            .iter()
+            .fold(Aabb::EMPTY, |acc, (_, bbox, _)| acc.union(bbox));
+        let index = nodes.len();
+        if items.len() <= MAX_LEAF_SIZE {
+            nodes.push(BvhNode::Leaf {
+                bbox,
+                start: offset,
+                end: offset + items.len(),
+            });
+            return index;
+        }
+
+        let centroids = items
+            .iter()
+            .fold(Aabb::EMPTY, |acc, (c, _, _)| acc.grow(*c));
+        let axis = centroids.longest_axis();
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| a.0[axis].total_cmp(&b.0[axis]));
+
+        nodes.push(BvhNode::Interior { bbox, right: 0 });
+        let (left, right) = items.split_at_mut(mid);
+        Self::build(left, offset, nodes);
+        let right_index = Self::build(right, offset + mid, nodes);
+        if let BvhNode::Interior { right, .. } = &mut nodes[index] {
+            *right = right_index;
+        }
+        index
+    }
+
+    /// Returns the number of objects.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Returns `true`, if there is no object.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
+
+impl Shape for Bvh {
+    fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let mut hit_info: Option<HitInfo> = None;
+        let mut closest_so_far = interval.max;
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node
+                .bbox()
+                .hit(ray, Interval::from_val(interval.min, closest_so_far))
+            {
+                continue;
+            }
+            match node {
+                BvhNode::Interior { right, .. } => {
+                    stack.push(*right);
+                    stack.push(index + 1);
+                }
+                BvhNode::Leaf { start, end, .. } => {
+                    for obj in &self.objects[*start..*end] {
+                        if let Some(info) =
+                            obj.hit(ray, Interval::from_val(interval.min, closest_so_far))
+                        {
+                            closest_so_far = info.t;
+                            hit_info = Some(info);
+                        }
+                    }
+                }
+            }
+        }
+        hit_info
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::EMPTY, |node| *node.bbox())
+    }
+
+    /// Counts the bounding boxes of nodes and the objects tested in the same order as `hit`.
+    fn traversal_cost(&self, ray: &Ray, interval: Interval) -> u32 {
+        let mut cost = 0;
+        let mut closest_so_far = interval.max;
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            cost += 1;
+            if !node
+                .bbox()
+                .hit(ray, Interval::from_val(interval.min, closest_so_far))
+            {
+                continue;
+            }
+            match node {
+                BvhNode::Interior { right, .. } => {
+                    stack.push(*right);
+                    stack.push(index + 1);
+                }
+                BvhNode::Leaf { start, end, .. } => {
+                    for obj in &self.objects[*start..*end] {
+                        let interval = Interval::from_val(interval.min, closest_so_far);
+                        cost += obj.traversal_cost(ray, interval);
+                        if let Some(info) = obj.hit(ray, interval) {
+                            closest_so_far = info.t;
+                        }
+                    }
+                }
+            }
+        }
+        cost
+    }
+
+    fn for_each_material(&self, f: &mut dyn FnMut(&Arc<dyn Material>)) {
+        self.objects.iter().for_each(|obj| obj.for_each_material(f));
+    }
+
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        self.objects.iter().flat_map(|obj| obj.emitters()).collect()
+    }
+}
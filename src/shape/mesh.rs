@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::material::{Material, Texture, VertexColorTexture};
+use crate::ray::Ray;
+use crate::shape::aabb::Aabb;
+use crate::shape::bvh::Bvh;
+use crate::shape::triangle::Triangle;
+use crate::shape::{Emitter, HitInfo, Shape};
+
+/// A container to store indexed triangle mesh data.
+///
+/// Per-vertex attributes are optional, empty vectors mean the attribute is absent.
+/// Otherwise, they must have the same length as `positions`.
+///
+/// # Arguments
+/// * `positions`   - The vertex positions.
+/// * `normals`     - The per-vertex normals.
+/// * `colors`      - The per-vertex colors in `[0.0, 1.0]`.
+/// * `uvs`         - The per-vertex texture coordinates.
+/// * `faces`       - The vertex indices of each triangle.
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<Color>,
+    pub uvs: Vec<(f64, f64)>,
+    pub faces: Vec<[usize; 3]>,
+}
+
+impl Mesh {
+    /// Constructs empty `Mesh`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true`, if the mesh has per-vertex normals.
+    pub fn has_normals(&self) -> bool {
+        !self.normals.is_empty()
+    }
+
+    /// Returns `true`, if the mesh has per-vertex colors.
+    pub fn has_colors(&self) -> bool {
+        !self.colors.is_empty()
+    }
+
+    /// Returns `true`, if the mesh has per-vertex texture coordinates.
+    pub fn has_uvs(&self) -> bool {
+        !self.uvs.is_empty()
+    }
+
+    /// Appends a polygon as triangle fan.
+    ///
+    /// # Arguments
+    /// * `indices` - The vertex indices of a polygon.
+    pub fn push_polygon(&mut self, indices: &[usize]) {
+        for i in 1..indices.len().saturating_sub(1) {
+            self.faces.push([indices[0], indices[i], indices[i + 1]]);
+        }
+    }
+
+    fn triangle(&self, face: &[usize; 3], material: Arc<dyn Material>) -> Triangle {
+        let [i0, i1, i2] = *face;
+        let tri = Triangle::new(
+            self.positions[i0],
+            self.positions[i1],
+            self.positions[i2],
+            material,
+        );
+        if self.has_normals() {
+            tri.with_normals([self.normals[i0], self.normals[i1], self.normals[i2]])
+        } else {
+            tri
+        }
+    }
+}
+
+/// A object shape with triangle mesh.
+pub struct TriangleMesh {
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    /// Constructs `TriangleMesh` whose triangles share the same material.
+    ///
+    /// # Arguments
+    /// * `mesh`        - The mesh data.
+    /// * `material`    - The material.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::shape::{Mesh, TriangleMesh};
+    ///
+    /// let mut mesh = Mesh::new();
+    /// mesh.positions = vec![
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(1.0, 0.0, 0.0),
+    ///     Vec3::new(1.0, 1.0, 0.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    /// ];
+    /// mesh.push_polygon(&[0, 1, 2, 3]);
+    ///
+    /// let quad = TriangleMesh::new(
+    ///     &mesh,
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::WHITE)))),
+    /// );
+    /// assert_eq!(quad.len(), 2);
+    /// ```
+    pub fn new(mesh: &Mesh, material: Arc<dyn Material>) -> Self {
+        Self::build(mesh, material, None)
+    }
+
+    /// Constructs `TriangleMesh` whose shading normals are perturbed by the tangent-space normal map.
+    ///
+    /// # Arguments
+    /// * `mesh`        - The mesh data, which must have texture coordinates to apply the normal map.
+    /// * `material`    - The material.
+    /// * `normal_map`  - The normal map whose RGB channels encode XYZ of the normal.
+    /// * `scale`       - The scale of XY of the normal.
+    pub fn with_normal_map(
+        mesh: &Mesh,
+        material: Arc<dyn Material>,
+        normal_map: Arc<dyn Texture>,
+        scale: f64,
+    ) -> Self {
+        Self::build(mesh, material, Some((normal_map, scale)))
+    }
+
+    fn build(
+        mesh: &Mesh,
+        material: Arc<dyn Material>,
+        normal_map: Option<(Arc<dyn Texture>, f64)>,
+    ) -> Self {
+        let triangles = mesh
+            .faces
+            .iter()
+            .map(|face| {
+                let mut tri = mesh.triangle(face, Arc::clone(&material));
+                if mesh.has_uvs() {
+                    let [i0, i1, i2] = *face;
+                    tri = tri.with_uvs([mesh.uvs[i0], mesh.uvs[i1], mesh.uvs[i2]]);
+                }
+                if let Some((texture, scale)) = &normal_map {
+                    tri = tri.with_normal_map(Arc::clone(texture), *scale);
+                }
+                Box::new(tri) as Box<dyn Shape>
+            })
+            .collect();
+        TriangleMesh {
+            bvh: Bvh::new(triangles),
+        }
+    }
+
+    /// Constructs `TriangleMesh` whose triangles are colored by the per-vertex colors.
+    ///
+    /// Each triangle gets its own material built by `material` from a `VertexColorTexture`.
+    /// If the mesh has no colors, vertices are treated as white.
+    ///
+    /// # Arguments
+    /// * `mesh`        - The mesh data.
+    /// * `material`    - A function to build a material from the texture of each triangle.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::Lambertian;
+    /// use raytrs::shape::{Mesh, TriangleMesh};
+    ///
+    /// let mut mesh = Mesh::new();
+    /// mesh.positions = vec![
+    ///     Vec3::new(0.0, 0.0, 0.0),
+    ///     Vec3::new(1.0, 0.0, 0.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    /// ];
+    /// mesh.colors = vec![Color::RED, Color::GREEN, Color::BLUE];
+    /// mesh.faces = vec![[0, 1, 2]];
+    ///
+    /// let tri = TriangleMesh::with_vertex_colors(&mesh, |texture| Arc::new(Lambertian::new(texture)));
+    /// ```
+    pub fn with_vertex_colors<F>(mesh: &Mesh, material: F) -> Self
+    where
+        F: Fn(Box<dyn Texture>) -> Arc<dyn Material>,
+    {
+        let triangles = mesh
+            .faces
+            .iter()
+            .map(|face| {
+                let colors = if mesh.has_colors() {
+                    face.map(|i| mesh.colors[i])
+                } else {
+                    [Color::WHITE; 3]
+                };
+                let texture = Box::new(VertexColorTexture::new(colors));
+                Box::new(mesh.triangle(face, material(texture))) as Box<dyn Shape>
+            })
+            .collect();
+        TriangleMesh {
+            bvh: Bvh::new(triangles),
+        }
+    }
+
+    /// Returns the number of triangles.
+    pub fn len(&self) -> usize {
+        self.bvh.len()
+    }
+
+    /// Returns `true`, if there is no triangle.
+    pub fn is_empty(&self) -> bool {
+        self.bvh.is_empty()
+    }
+}
+
+impl Shape for TriangleMesh {
+    fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
+        self.bvh.hit(ray, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+
+    fn traversal_cost(&self, ray: &Ray, interval: Interval) -> u32 {
+        self.bvh.traversal_cost(ray, interval)
+    }
+
+    fn for_each_material(&self, f: &mut dyn FnMut(&Arc<dyn Material>)) {
+        self.bvh.for_each_material(f);
+    }
+
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        self.bvh.emitters()
+    }
+}
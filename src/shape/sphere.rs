@@ -2,10 +2,15 @@ use std::sync::Arc;
 
 use std::f64::consts::PI;
 
+use crate::geometry::vec3::orthonormal_basis;
+
 use crate::geometry::Vec3;
 use crate::interval::Interval;
+use crate::material::bsdf::uniform_sphere;
 use crate::material::Material;
 use crate::ray::Ray;
+use crate::shape::aabb::Aabb;
+use crate::shape::Emitter;
 use crate::shape::HitInfo;
 use crate::shape::Shape;
 
@@ -15,6 +20,7 @@ use crate::shape::Shape;
 /// * `center` - The center position.
 /// * `radius` - The radius.
 /// * `material` - The material.
+#[derive(Clone)]
 pub struct Sphere {
     center: Vec3,
     radius: f64,
@@ -42,7 +48,7 @@ impl Sphere {
     /// let s = Sphere::new(
     ///     Vec3::new(0.0, -1000.0, 0.0),
     ///     1000.0,
-    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::random())))),
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::new(0.5, 0.5, 0.5))))),
     /// );
     /// ```
     pub fn new(center: Vec3, radius: f64, material: Arc<dyn Material>) -> Self {
@@ -53,7 +59,9 @@ impl Sphere {
         }
     }
 
-    /// Returns u, v coords.
+    /// Returns u, v coords of the unit vector `n` from the center to the point.
+    ///
+    /// The vector must be normalized, otherwise `asin` of the point off the unit sphere is NaN.
     ///
     /// $$
     /// u = \frac{\phi}{2\pi}, \quad \frac{\theta}{2\pi}
@@ -74,13 +82,21 @@ impl Sphere {
     /// u = 1 - \frac{\phi + \pi}{2\pi}, \quad
     /// v = \frac{\theta + \frac{\pi}{2}}{\pi}
     /// $$
-    fn get_uv(&self, p: Vec3) -> (f64, f64) {
-        let phi = p.z().atan2(*p.x());
-        let theta = p.y().asin();
+    fn get_uv(&self, n: Vec3) -> (f64, f64) {
+        let phi = n.z().atan2(*n.x());
+        let theta = n.y().asin();
         let u = 1.0 - (phi + PI) / (2.0 * PI);
         let v = (theta + PI / 2.0) / PI;
         (u, v)
     }
+
+    /// Returns the cosine of the half angle of the cone subtended by the sphere from `origin`,
+    /// or `None` if `origin` is inside the sphere.
+    fn cos_theta_max(&self, origin: Vec3) -> Option<f64> {
+        let r2 = self.radius.powi(2);
+        let d2 = (self.center - origin).norm_squared();
+        (d2 > r2).then(|| (1.0 - r2 / d2).sqrt())
+    }
 }
 
 #[cfg_attr(doc, katexit::katexit)]
@@ -89,8 +105,10 @@ impl Shape for Sphere {
     ///
     /// 1. Computes discriminant `D`.
     /// 2. If `D` > 0.0, it means ray hit.
-    /// 3. Computes the `t` which is the time ray hits to sphere.
-    /// 4. Computes the unit normal vector from center to the point at `t`.
+    /// 3. Computes the `t` which is the time ray hits to sphere,
+    ///    which is the nearer root within `interval`, or the farther root if the nearer one is out of it,
+    ///    e.g. the ray starts inside the sphere.
+    /// 4. Computes the unit normal vector from center to the point at `t`, which gives the u, v coords.
     ///
     /// $$
     /// (\vec{p} - \vec{c}) \cdot (\vec{p} - \vec{c}) = r^2
@@ -112,6 +130,33 @@ impl Shape for Sphere {
     /// # Arguments
     /// * `ray`         - Ray from camera.
     /// * `interval`    - Time range of ray.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::interval::Interval;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::ray::Ray;
+    /// use raytrs::shape::{Shape, Sphere};
+    ///
+    /// let sphere = Sphere::new(
+    ///     Vec3::new(0.0, 0.0, -5.0),
+    ///     2.0,
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::WHITE)))),
+    /// );
+    /// // The ray from outside hits the near side.
+    /// let ray = Ray::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+    /// let info = sphere.hit(&ray, Interval::from_val(1e-3, f64::INFINITY)).unwrap();
+    /// assert!((info.t - 3.0).abs() < 1e-8);
+    /// // The ray from inside hits the far side.
+    /// let ray = Ray::new(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, -1.0));
+    /// let info = sphere.hit(&ray, Interval::from_val(1e-3, f64::INFINITY)).unwrap();
+    /// assert!((info.t - 2.0).abs() < 1e-8);
+    /// assert!((0.0..=1.0).contains(&info.u) && (0.0..=1.0).contains(&info.v));
+    /// ```
     fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
         let oc = *ray.origin() - self.center;
         let a = ray.direction().norm_squared();
@@ -120,24 +165,120 @@ impl Shape for Sphere {
         let d = b.powi(2) - 4.0 * a * c;
         if d > 0.0 {
             let root = d.sqrt();
-            let t = if (-b - root) > 0.0 {
-                (-b - root) / (2.0 * a)
-            } else {
-                (-b + root) / (2.0 * a)
-            };
-            if interval.min < t && t < interval.max {
+            let t = [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+                .into_iter()
+                .find(|t| interval.min < *t && *t < interval.max);
+            if let Some(t) = t {
                 let p = ray.at(t);
-                let (u, v) = self.get_uv(p);
-                return Some(HitInfo::new(
-                    t,
-                    p,
-                    (p - self.center) / self.radius,
-                    Arc::clone(&self.material),
-                    u,
-                    v,
-                ));
+                let n = (p - self.center) / self.radius;
+                let (u, v) = self.get_uv(n);
+                return Some(HitInfo::new(t, p, n, Arc::clone(&self.material), u, v));
             }
         }
         None
     }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::splat(self.radius.abs());
+        Aabb::new(self.center - r, self.center + r)
+    }
+
+    fn for_each_material(&self, f: &mut dyn FnMut(&Arc<dyn Material>)) {
+        f(&self.material);
+    }
+
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        if self.material.is_emissive() {
+            vec![Box::new(self.clone())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl Emitter for Sphere {
+    /// Returns the point uniformly distributed on the sphere.
+    fn sample_surface(&self, u: (f64, f64)) -> Option<(HitInfo, f64)> {
+        let n = uniform_sphere(u);
+        let p = self.center + n * self.radius.abs();
+        let n = (p - self.center) / self.radius;
+        let (u, v) = self.get_uv(n);
+        let pdf = 1.0 / self.area();
+        Some((
+            HitInfo::new(0.0, p, n, Arc::clone(&self.material), u, v),
+            pdf,
+        ))
+    }
+
+    fn area(&self) -> f64 {
+        4.0 * PI * self.radius * self.radius
+    }
+
+    /// Returns the power of the Lambertian emitter, whose radiance is emitted at the top of the sphere.
+    fn power(&self) -> f64 {
+        let n = Vec3::new(0.0, 1.0, 0.0);
+        let (u, v) = self.get_uv(n);
+        let radiance = self.material.emitted(u, v, self.center + n * self.radius);
+        PI * radiance.luminance() * 4.0 * PI * self.radius * self.radius
+    }
+
+    /// Returns the uniform PDF over the cone of directions subtended by the sphere,
+    /// or over all directions if `origin` is inside the sphere.
+    ///
+    /// $$
+    /// p = \frac{1}{2\pi(1 - \cos\theta_{max})}, \quad
+    /// \cos\theta_{max} = \sqrt{1 - \frac{r^2}{|\vec{c} - \vec{o}|^2}}
+    /// $$
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::material::{ColorTexture, DiffuseLight};
+    /// use raytrs::shape::{Emitter, Shape, Sphere};
+    ///
+    /// let light = Sphere::new(
+    ///     Vec3::new(0.0, 10.0, 0.0),
+    ///     1.0,
+    ///     Arc::new(DiffuseLight::new(Box::new(ColorTexture::new(Color::WHITE)))),
+    /// );
+    /// assert_eq!(light.emitters().len(), 1);
+    ///
+    /// let origin = Vec3::zeros();
+    /// let direction = light.random_direction(origin, (0.3, 0.7));
+    /// assert!(light.pdf_value(origin, direction) > 0.0);
+    /// assert_eq!(light.pdf_value(origin, Vec3::new(0.0, -1.0, 0.0)), 0.0);
+    /// ```
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+        if self
+            .hit(
+                &Ray::new(origin, direction),
+                Interval::from_val(1e-3, f64::INFINITY),
+            )
+            .is_none()
+        {
+            return 0.0;
+        }
+        match self.cos_theta_max(origin) {
+            Some(cos_theta_max) => 1.0 / (2.0 * PI * (1.0 - cos_theta_max)),
+            None => 1.0 / (4.0 * PI),
+        }
+    }
+
+    fn random_direction(&self, origin: Vec3, u: (f64, f64)) -> Vec3 {
+        let Some(cos_theta_max) = self.cos_theta_max(origin) else {
+            return uniform_sphere(u);
+        };
+        let (r1, r2) = u;
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).max(0.0).sqrt();
+
+        let w = (self.center - origin).as_unit();
+        let (u, v) = orthonormal_basis(w);
+        u * (phi.cos() * sin_theta) + v * (phi.sin() * sin_theta) + w * z
+    }
 }
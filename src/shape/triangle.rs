@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::geometry::Vec3;
+use crate::interval::Interval;
+use crate::material::{Material, Texture};
+use crate::ray::Ray;
+use crate::shape::aabb::Aabb;
+use crate::shape::Emitter;
+use crate::shape::HitInfo;
+use crate::shape::Shape;
+
+/// A object shape with triangle.
+///
+/// # Arguments
+/// * `vertices`    - The vertex positions in counter-clockwise order.
+/// * `normals`     - The optional per-vertex normals.
+/// * `uvs`         - The optional per-vertex texture coordinates.
+/// * `normal_map`  - The optional tangent-space normal map and its scale.
+/// * `material`    - The material.
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    vertices: [Vec3; 3],
+    normals: Option<[Vec3; 3]>,
+    uvs: Option<[(f64, f64); 3]>,
+    normal_map: Option<(Arc<dyn Texture>, f64)>,
+    material: Arc<dyn Material>,
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl Triangle {
+    /// Constructs `Triangle` from vertices.
+    ///
+    /// # Arguments
+    /// * `v0`          - The first vertex.
+    /// * `v1`          - The second vertex.
+    /// * `v2`          - The third vertex.
+    /// * `material`    - The material.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use raytrs::color::Color;
+    /// use raytrs::geometry::Vec3;
+    /// use raytrs::interval::Interval;
+    /// use raytrs::material::{ColorTexture, Lambertian};
+    /// use raytrs::ray::Ray;
+    /// use raytrs::shape::{Shape, Triangle};
+    ///
+    /// let tri = Triangle::new(
+    ///     Vec3::new(-1.0, -1.0, 0.0),
+    ///     Vec3::new(1.0, -1.0, 0.0),
+    ///     Vec3::new(0.0, 1.0, 0.0),
+    ///     Arc::new(Lambertian::new(Box::new(ColorTexture::new(Color::WHITE)))),
+    /// );
+    ///
+    /// let ray = Ray::new(Vec3::new(0.0, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0));
+    /// let info = tri.hit(&ray, Interval::from_val(0.0, f64::INFINITY)).unwrap();
+    /// assert_eq!(info.t, 2.0);
+    /// assert_eq!(info.n, Vec3::new(0.0, 0.0, 1.0));
+    /// ```
+    pub fn new(v0: Vec3, v1: Vec3, v2: Vec3, material: Arc<dyn Material>) -> Self {
+        Triangle {
+            vertices: [v0, v1, v2],
+            normals: None,
+            uvs: None,
+            normal_map: None,
+            material,
+        }
+    }
+
+    /// Sets per-vertex normals, which are interpolated for smooth shading.
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    /// Sets per-vertex texture coordinates.
+    pub fn with_uvs(mut self, uvs: [(f64, f64); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
+    /// Sets tangent-space normal map, which is used only if the triangle has texture coordinates.
+    ///
+    /// # Arguments
+    /// * `texture` - The normal map whose RGB channels encode XYZ of the normal.
+    /// * `scale`   - The scale of XY of the normal.
+    pub fn with_normal_map(mut self, texture: Arc<dyn Texture>, scale: f64) -> Self {
+        self.normal_map = Some((texture, scale));
+        self
+    }
+
+    /// Returns the vertex positions.
+    pub fn vertices(&self) -> &[Vec3; 3] {
+        &self.vertices
+    }
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl Shape for Triangle {
+    /// Returns `HitInfo` if ray hits to itself with Möller–Trumbore algorithm.
+    ///
+    /// The hit point is expressed by the barycentric coordinates $(b_1, b_2)$ as follows.
+    ///
+    /// $$
+    /// \vec{o} + t\vec{d} = (1 - b_1 - b_2)\vec{v}_0 + b_1\vec{v}_1 + b_2\vec{v}_2
+    /// $$
+    ///
+    /// If the triangle has no texture coordinates, $(b_1, b_2)$ is used as `u`, `v`.
+    ///
+    /// # Arguments
+    /// * `ray`         - Ray from camera.
+    /// * `interval`    - Time range of ray.
+    fn hit(&self, ray: &Ray, interval: Interval) -> Option<HitInfo> {
+        const EPSILON: f64 = 1e-12;
+
+        let [v0, v1, v2] = self.vertices;
+        let e1 = v1 - v0;
+        let e2 = v2 - v0;
+        let pv = ray.direction().cross(e2);
+        let det = e1.dot(pv);
+        if det.abs() < EPSILON {
+            return None;
+        }
+        let inv_det = det.recip();
+
+        let tv = *ray.origin() - v0;
+        let b1 = tv.dot(pv) * inv_det;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        let qv = tv.cross(e1);
+        let b2 = ray.direction().dot(qv) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        let t = e2.dot(qv) * inv_det;
+        if !interval.surrounds(t) {
+            return None;
+        }
+
+        let b0 = 1.0 - b1 - b2;
+        let n = match self.normals {
+            Some([n0, n1, n2]) => (n0 * b0 + n1 * b1 + n2 * b2).as_unit(),
+            None => e1.cross(e2).as_unit(),
+        };
+        let (u, v) = match self.uvs {
+            Some([uv0, uv1, uv2]) => (
+                uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2,
+                uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2,
+            ),
+            None => (b1, b2),
+        };
+        let p = ray.at(t);
+        let n = match (&self.normal_map, self.uvs) {
+            (Some((texture, scale)), Some(uvs)) => {
+                perturb_normal(n, e1, e2, uvs, texture.value(u, v, p), *scale)
+            }
+            _ => n,
+        };
+        Some(HitInfo::new(t, p, n, Arc::clone(&self.material), u, v))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        const PADDING: f64 = 1e-6;
+
+        let [v0, v1, v2] = self.vertices;
+        let bbox = Aabb::new(v0, v1).grow(v2);
+        Aabb::new(bbox.min - PADDING, bbox.max + PADDING)
+    }
+
+    fn for_each_material(&self, f: &mut dyn FnMut(&Arc<dyn Material>)) {
+        f(&self.material);
+    }
+
+    fn emitters(&self) -> Vec<Box<dyn Emitter>> {
+        if self.material.is_emissive() {
+            vec![Box::new(self.clone())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg_attr(doc, katexit::katexit)]
+impl Emitter for Triangle {
+    /// Returns the point uniformly distributed on the triangle.
+    fn sample_surface(&self, u: (f64, f64)) -> Option<(HitInfo, f64)> {
+        let (mut b1, mut b2) = u;
+        if b1 + b2 > 1.0 {
+            (b1, b2) = (1.0 - b1, 1.0 - b2);
+        }
+        let b0 = 1.0 - b1 - b2;
+        let [v0, v1, v2] = self.vertices;
+        let area = self.area();
+        if area <= 0.0 {
+            return None;
+        }
+        let n = match self.normals {
+            Some([n0, n1, n2]) => (n0 * b0 + n1 * b1 + n2 * b2).as_unit(),
+            None => (v1 - v0).cross(v2 - v0).as_unit(),
+        };
+        let (u, v) = match self.uvs {
+            Some([uv0, uv1, uv2]) => (
+                uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2,
+                uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2,
+            ),
+            None => (b1, b2),
+        };
+        let p = v0 * b0 + v1 * b1 + v2 * b2;
+        let info = HitInfo::new(0.0, p, n, Arc::clone(&self.material), u, v);
+        Some((info, 1.0 / area))
+    }
+
+    fn area(&self) -> f64 {
+        let [v0, v1, v2] = self.vertices;
+        0.5 * (v1 - v0).cross(v2 - v0).norm()
+    }
+
+    /// Returns the power of the Lambertian emitter, whose radiance is emitted at the centroid.
+    fn power(&self) -> f64 {
+        let [v0, v1, v2] = self.vertices;
+        let (u, v) = match self.uvs {
+            Some([uv0, uv1, uv2]) => ((uv0.0 + uv1.0 + uv2.0) / 3.0, (uv0.1 + uv1.1 + uv2.1) / 3.0),
+            None => (1.0 / 3.0, 1.0 / 3.0),
+        };
+        let radiance = self.material.emitted(u, v, (v0 + v1 + v2) / 3.0);
+        let area = 0.5 * (v1 - v0).cross(v2 - v0).norm();
+        std::f64::consts::PI * radiance.luminance() * area
+    }
+
+    /// Returns the PDF of the uniform point on the triangle converted to solid angle.
+    ///
+    /// $$
+    /// p = \frac{t^2|\vec{d}|^2}{A|\cos\theta|}
+    /// $$
+    ///
+    /// where $A$ is the area and $\theta$ is the angle between the direction and the normal.
+    fn pdf_value(&self, origin: Vec3, direction: Vec3) -> f64 {
+        let Some(info) = self.hit(
+            &Ray::new(origin, direction),
+            Interval::from_val(1e-3, f64::INFINITY),
+        ) else {
+            return 0.0;
+        };
+        let [v0, v1, v2] = self.vertices;
+        let cross = (v1 - v0).cross(v2 - v0);
+        let area = 0.5 * cross.norm();
+        let cos = (direction.dot(cross) / (direction.norm() * cross.norm())).abs();
+        if area * cos <= 0.0 {
+            return 0.0;
+        }
+        info.t.powi(2) * direction.norm_squared() / (area * cos)
+    }
+
+    fn random_direction(&self, origin: Vec3, u: (f64, f64)) -> Vec3 {
+        let (mut b1, mut b2) = u;
+        if b1 + b2 > 1.0 {
+            (b1, b2) = (1.0 - b1, 1.0 - b2);
+        }
+        let [v0, v1, v2] = self.vertices;
+        v0 + (v1 - v0) * b1 + (v2 - v0) * b2 - origin
+    }
+}
+
+/// Returns the normal perturbed by the normal map color `c` in the tangent space.
+///
+/// The tangent and bitangent are computed from the derivatives of texture coordinates along the edges.
+fn perturb_normal(n: Vec3, e1: Vec3, e2: Vec3, uvs: [(f64, f64); 3], c: Color, scale: f64) -> Vec3 {
+    let (du1, dv1) = (uvs[1].0 - uvs[0].0, uvs[1].1 - uvs[0].1);
+    let (du2, dv2) = (uvs[2].0 - uvs[0].0, uvs[2].1 - uvs[0].1);
+    let det = du1 * dv2 - dv1 * du2;
+    if det.abs() < 1e-12 {
+        return n;
+    }
+    let tangent = (e1 * dv2 - e2 * dv1) / det;
+    let bitangent = (e2 * du1 - e1 * du2) / det;
+
+    // Gram-Schmidt orthogonalization keeps the handedness of the texture space.
+    let t = (tangent - n * n.dot(tangent)).as_unit();
+    let b = if n.cross(t).dot(bitangent) < 0.0 {
+        -n.cross(t)
+    } else {
+        n.cross(t)
+    };
+
+    let x = (2.0 * c.r - 1.0) * scale;
+    let y = (2.0 * c.g - 1.0) * scale;
+    let z = 2.0 * c.b - 1.0;
+    let perturbed = t * x + b * y + n * z;
+    if perturbed.is_close(0.0) {
+        n
+    } else {
+        perturbed.as_unit()
+    }
+}
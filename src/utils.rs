@@ -1,47 +1,73 @@
-use rand::{
-    distributions::{
-        uniform::{SampleRange, SampleUniform},
-        Distribution, Standard,
-    },
-    thread_rng,
+pub(crate) mod rng;
+
+use rand::distributions::{
+    uniform::{SampleRange, SampleUniform},
+    Distribution, Standard,
 };
 
+use self::rng::Rng as _Rng;
+
+/// A shortcut of `raytrs::utils::rng::Rng`
+pub type Rng = _Rng;
+
 /// Generate a random value.
 ///
+/// # Arguments
+/// * `rng`     - The random generator.
+///
 /// # Example
 ///
 /// ```
-/// use raytrs::utils::random;
+/// use raytrs::utils::{random, Rng};
 ///
-/// let v = random::<f64>();
+/// let mut rng = Rng::new(0);
+/// let v = random::<f64>(&mut rng);
 /// assert!(0.0 <= v && v <= 1.0);
 /// ```
-pub fn random<T>() -> T
+pub fn random<T>(rng: &mut Rng) -> T
 where
     Standard: Distribution<T>,
 {
-    let mut rng = thread_rng();
-    Standard.sample(&mut rng)
+    Standard.sample(rng)
 }
 
 /// Generate a random value in specified range.
 ///
 /// # Arguments
+/// * `rng`     - The random generator.
 /// * `range`   - The Range to sample number.
 ///
 /// # Example
 /// ```
-/// use raytrs::utils::random_range;
+/// use raytrs::utils::{random_range, Rng};
 ///
-/// let v = random_range(0..100);
+/// let mut rng = Rng::new(0);
+/// let v = random_range(&mut rng, 0..100);
 /// assert!(0 <= v && v <= 100);
 /// ```
-pub fn random_range<T, R>(range: R) -> T
+pub fn random_range<T, R>(rng: &mut Rng, range: R) -> T
 where
     T: SampleUniform,
     R: SampleRange<T>,
 {
     assert!(!range.is_empty(), "cannot sample empty range");
-    let mut rng = thread_rng();
-    range.sample_single(&mut rng)
+    range.sample_single(rng)
+}
+
+/// Returns 64-bit FNV-1a hash of bytes, which is stable across platforms and builds.
+///
+/// # Arguments
+/// * `bytes`   - The bytes to hash.
+///
+/// # Example
+/// ```
+/// use raytrs::utils::hash_bytes;
+///
+/// assert_eq!(hash_bytes(b""), 0xcbf29ce484222325);
+/// assert_ne!(hash_bytes(b"a"), hash_bytes(b"b"));
+/// ```
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, &b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    })
 }
@@ -0,0 +1,119 @@
+use rand::RngCore;
+
+/// A seedable random generator with xoshiro256++ algorithm.
+///
+/// The same seed always produces the same sequence on any platform and any thread.
+///
+/// # Examples
+/// ```
+/// use raytrs::utils::Rng;
+///
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+/// assert_eq!(a.next_f64(), b.next_f64());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    s: [u64; 4],
+}
+
+impl Rng {
+    /// Constructs `Rng` from the seed.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed value.
+    pub fn new(seed: u64) -> Self {
+        let mut x = seed;
+        let s = [
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+            splitmix64(&mut x),
+        ];
+        Rng { s }
+    }
+
+    /// Constructs `Rng` for a sample of a pixel.
+    ///
+    /// Each sample has its own independent sequence,
+    /// so the result does not depend on the order of samples to be computed.
+    ///
+    /// # Arguments
+    /// * `seed`    - The seed of the whole image.
+    /// * `pixel`   - The pixel index.
+    /// * `sample`  - The sample index in the pixel.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::utils::Rng;
+    ///
+    /// assert_eq!(Rng::for_sample(0, 1, 2), Rng::for_sample(0, 1, 2));
+    /// assert_ne!(Rng::for_sample(0, 1, 2), Rng::for_sample(0, 2, 1));
+    /// ```
+    pub fn for_sample(seed: u64, pixel: u64, sample: u64) -> Self {
+        let mut x = seed;
+        let h = splitmix64(&mut x) ^ pixel;
+        x = h;
+        let h = splitmix64(&mut x) ^ sample;
+        Self::new(h)
+    }
+
+    /// Returns the next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = (s[0].wrapping_add(s[3])).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Returns the next random `f64` in `[0.0, 1.0)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use raytrs::utils::Rng;
+    ///
+    /// let mut rng = Rng::new(0);
+    /// let v = rng.next_f64();
+    /// assert!(0.0 <= v && v < 1.0);
+    /// ```
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        (Rng::next_u64(self) >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Rng::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = Rng::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Advances the state and returns the next value of SplitMix64, which is used to expand seeds.
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}